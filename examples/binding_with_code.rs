@@ -45,6 +45,8 @@ fn bind_keys(mut im: ResMut<InputMapper<GameState>>) {
         .bind_mouse_axis_move(GameState::Default, MouseAxis::PositiveX, "look_right")
         .bind_mouse_axis_move(GameState::Default, MouseAxis::PositiveY, "look_down")
         .bind_mouse_axis_move(GameState::Default, MouseAxis::NegativeY, "look_up");
+    // Everything bound so far is the default, later changes are user overrides.
+    im.set_defaults();
 }
 
 fn logger(
@@ -1,10 +1,9 @@
-use bevy::{
-    input::{keyboard::KeyboardInput, mouse::MouseButtonInput},
-    prelude::*,
-    utils::HashMap,
-};
+use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
-use bevy_input_mapper::{InputMapper, InputMapperPlugin};
+use bevy_input_mapper::{
+    input::{binding::InputDevice, events::RebindCompleted, rebind::RebindOptions},
+    InputMapper, InputMapperPlugin,
+};
 
 /// Here, we define a State for Scenario.
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
@@ -14,25 +13,14 @@ pub enum GameState {
     Swimming,
 }
 
-#[derive(Default, Resource)]
-pub struct UiState {
-    /// action name -> button name
-    button_names: HashMap<String, String>,
-    /// If the button pressed for re-mapping.
-    is_listening: bool,
-    /// If the button pressed for re-mapping, which one?
-    which_action_listening: String,
-}
-
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugins(EguiPlugin)
         .add_plugins(InputMapperPlugin::<GameState>::new())
         .add_state::<GameState>()
-        .init_resource::<UiState>()
         .add_systems(Startup, bind_keys)
-        .add_systems(Update, remapper)
+        .add_systems(Update, (remapper, rebind_logger))
         .run();
 }
 
@@ -43,55 +31,39 @@ fn bind_keys(mut im: ResMut<InputMapper<GameState>>) {
         // On swimming Scenario/State, pressing Space or Gamepad South triggers swim_up action.
         .bind_keyboard_key_press(GameState::Swimming, KeyCode::Space, "swim_up")
         .bind_gamepad_button_press(GameState::Swimming, GamepadButtonType::South, "swim_up");
+    // Everything bound so far is the default, later changes are user overrides.
+    im.set_defaults();
 }
 
-fn remapper(
-    mut ctx: EguiContexts,
-    mut ui_state: ResMut<UiState>,
-    mut keyboard: EventReader<KeyboardInput>,
-    mut mouse: EventReader<MouseButtonInput>,
-    mut im: ResMut<InputMapper<GameState>>,
-) {
+fn remapper(mut ctx: EguiContexts, mut im: ResMut<InputMapper<GameState>>) {
     egui::Window::new("Keyboard Actions (Default Scenario)").show(ctx.ctx_mut(), |ui| {
-        if ui_state.is_listening {
-            for ev in mouse.iter() {
-                im.bind_mouse_button_press(
-                    GameState::Default,
-                    ev.button,
-                    ui_state.which_action_listening.clone(),
-                );
-                ui_state.is_listening = false;
-                ui_state.which_action_listening = String::new();
-            }
-            for ev in keyboard.iter() {
-                if let Some(code) = ev.key_code {
-                    im.bind_keyboard_key_press(
-                        GameState::Default,
-                        code,
-                        ui_state.which_action_listening.clone(),
-                    );
-                }
-                ui_state.is_listening = false;
-                ui_state.which_action_listening = String::new();
-            }
-        }
         for action in im.list_scenario_actions_keyboard_mouse(GameState::Default) {
-            if ui_state.which_action_listening == action {
-                ui_state
-                    .button_names
-                    .insert(action.clone(), format!("Listening {}", action.clone()));
+            let label = if im.rebinding_action() == Some((GameState::Default, action.as_str())) {
+                format!("Listening {action}")
             } else {
-                ui_state
-                    .button_names
-                    .insert(action.clone(), format!("Bind {}", action.clone()));
+                let inputs = im
+                    .action_input_labels(GameState::Default, &action)
+                    .into_iter()
+                    .map(|label| label.name)
+                    .collect::<Vec<_>>();
+                format!("Bind {action} [{}]", inputs.join(", "))
+            };
+            if ui.button(label).clicked() {
+                // Mapping is suspended until the next key or mouse input is captured.
+                // Escape cancels.
+                im.start_rebind(
+                    GameState::Default,
+                    &action,
+                    RebindOptions {
+                        devices: vec![InputDevice::Keyboard, InputDevice::Mouse],
+                        ..default()
+                    },
+                );
             }
-
-            if ui
-                .button(ui_state.button_names.get(&action.clone()).unwrap())
-                .clicked()
+            if im.is_action_overridden(GameState::Default, &action)
+                && ui.button(format!("Reset {action}")).clicked()
             {
-                ui_state.is_listening = true;
-                ui_state.which_action_listening = action;
+                im.reset_action_to_defaults(GameState::Default, &action);
             }
         }
     });
@@ -101,3 +73,13 @@ fn remapper(
         }
     });
 }
+
+fn rebind_logger(mut completed: EventReader<RebindCompleted<GameState>>) {
+    for ev in completed.iter() {
+        if let Some(conflict) = &ev.conflict {
+            info!("{:?} moved from {} to {}", ev.input, conflict, ev.action);
+        } else {
+            info!("{:?} bound to {}", ev.input, ev.action);
+        }
+    }
+}
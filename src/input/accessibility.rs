@@ -0,0 +1,219 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{AutoBinder, InputMapper};
+
+/// How pressing an action's inputs turns into the action's value.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PressMode {
+    /// Active while held.
+    #[default]
+    Hold,
+    /// The first press turns the action on, the next one off. Turns "hold to sprint" into a toggle.
+    Toggle,
+    /// Pressing and releasing both send a one-frame tap. For actions the game treats as
+    /// toggles, e.g. "toggle crouch", this keeps the toggle on only while held.
+    HoldAsTaps,
+}
+
+/// Repeats a held action like OS key repeat: it is pressed, then pressed again every
+/// `interval` once held for `delay`. Every repeat sends `InputActionStarted`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AutoRepeat {
+    pub delay: Duration,
+    pub interval: Duration,
+}
+
+impl Default for AutoRepeat {
+    fn default() -> Self {
+        AutoRepeat {
+            delay: Duration::from_millis(400),
+            interval: Duration::from_millis(80),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct RepeatState {
+    held_for: Duration,
+    next_repeat: Duration,
+    on: bool,
+}
+
+impl RepeatState {
+    /// Just pressed, the first frame is a press.
+    fn new(repeat: &AutoRepeat) -> Self {
+        RepeatState {
+            held_for: Duration::ZERO,
+            next_repeat: repeat.delay,
+            on: true,
+        }
+    }
+    /// Turns a held action into a series of one-frame presses.
+    fn advance(&mut self, repeat: &AutoRepeat, delta: Duration, value: f32) -> f32 {
+        self.held_for += delta;
+        if self.on {
+            // A frame apart, so every repeat starts the action again.
+            self.on = false;
+            0.
+        } else if self.held_for >= self.next_repeat {
+            self.on = true;
+            self.next_repeat = self.held_for + repeat.interval;
+            value
+        } else {
+            0.
+        }
+    }
+}
+
+/// Taps [`PressMode::HoldAsTaps`] keeps in store while mashing faster than it can send them.
+/// Further taps are dropped in pairs, so the toggles still end up matching the input.
+const MAX_PENDING_TAPS: u8 = 4;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct PressModeState {
+    raw_previous: f32,
+    toggled: bool,
+    pending_taps: u8,
+    tapping: bool,
+}
+
+impl PressModeState {
+    fn resolve(&mut self, mode: PressMode, raw_value: f32) -> f32 {
+        if mode == PressMode::Hold {
+            return raw_value;
+        }
+        let pressed = self.raw_previous == 0. && raw_value > 0.;
+        let released = self.raw_previous > 0. && raw_value == 0.;
+        self.raw_previous = raw_value;
+        if mode == PressMode::Toggle {
+            self.toggled ^= pressed;
+            if self.toggled {
+                1.
+            } else {
+                0.
+            }
+        } else {
+            self.pending_taps += (pressed as u8) + (released as u8);
+            if self.pending_taps > MAX_PENDING_TAPS {
+                self.pending_taps -= 2;
+            }
+            if self.tapping {
+                // A frame apart, so back to back taps are both seen.
+                self.tapping = false;
+                0.
+            } else if self.pending_taps > 0 {
+                self.pending_taps -= 1;
+                self.tapping = true;
+                1.
+            } else {
+                0.
+            }
+        }
+    }
+}
+
+/// What resolving an action's value remembers between frames.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct ResolveState {
+    press_mode: PressModeState,
+    repeat: Option<RepeatState>,
+    threshold_pressed: bool,
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Sets the action's press mode in every scenario, e.g. from the player's accessibility settings.
+    pub fn set_press_mode(&mut self, action: impl ToString, mode: PressMode) -> &mut Self {
+        let action = action.to_string();
+        if mode == PressMode::Hold {
+            self.press_modes.unbind(action);
+        } else {
+            self.press_modes.bind(action, mode);
+        }
+        self
+    }
+    pub fn press_mode(&self, action: &str) -> PressMode {
+        self.press_modes.get(action).copied().unwrap_or_default()
+    }
+    /// Every action that isn't [`PressMode::Hold`], sorted by action name, to save with
+    /// the player's profile.
+    pub fn press_modes(&self) -> Vec<(String, PressMode)> {
+        let mut modes = self
+            .press_modes
+            .iter()
+            .map(|(action, mode)| (action.to_owned(), *mode))
+            .collect::<Vec<_>>();
+        modes.sort_by_cached_key(|(action, _)| action.to_owned());
+        modes
+    }
+
+    /// Repeats the action while held, in every scenario. For menu navigation or rapid-fire.
+    pub fn set_auto_repeat(&mut self, action: impl ToString, repeat: AutoRepeat) -> &mut Self {
+        self.auto_repeat.bind(action.to_string(), repeat);
+        self
+    }
+    /// Stops repeating the action while held.
+    pub fn clear_auto_repeat(&mut self, action: &str) -> &mut Self {
+        self.auto_repeat.unbind(action.to_owned());
+        self
+    }
+    pub fn auto_repeat(&self, action: &str) -> Option<AutoRepeat> {
+        self.auto_repeat.get(action).copied()
+    }
+
+    /// Turns the raw values written by devices into the action values events are sent for.
+    pub(crate) fn resolve_action_values(&mut self, delta: Duration) {
+        let raw = self.raw_action_value.clone();
+        for (key, raw_value) in raw {
+            if self.resolved_values.contains(&key) {
+                self.resolve_state.remove(&key);
+                self.action_value.bind(key, raw_value);
+                continue;
+            }
+            let mut state = self.resolve_state.remove(&key).unwrap_or_default();
+            let value = self.resolve_value(&key.1, raw_value, delta, &mut state);
+            self.resolve_state.bind(key.clone(), state);
+            self.action_value.bind(key, value);
+        }
+    }
+    /// Sets a value that is already resolved, so it becomes the action value as is.
+    pub(crate) fn set_resolved_value(&mut self, key: (T, String), value: f32) {
+        self.resolved_values.insert(key.clone());
+        self.raw_action_value.bind(key, value);
+    }
+    /// Resolves one action's raw value: its [`ButtonThreshold`](crate::input::navigation::ButtonThreshold),
+    /// [`PressMode`] and [`AutoRepeat`]. Shared by the mapper's own values and every
+    /// [`ActionState`](crate::input::action_state::ActionState).
+    pub(crate) fn resolve_value(
+        &self,
+        action: &str,
+        raw_value: f32,
+        delta: Duration,
+        state: &mut ResolveState,
+    ) -> f32 {
+        let raw_value = match self.button_thresholds.get(action) {
+            Some(threshold) => threshold.apply(&mut state.threshold_pressed, raw_value),
+            None => raw_value,
+        };
+        let value = state.press_mode.resolve(self.press_mode(action), raw_value);
+        match self.auto_repeat.get(action) {
+            Some(repeat) if value > 0. => match state.repeat.as_mut() {
+                Some(held) => held.advance(repeat, delta, value),
+                None => {
+                    state.repeat = Some(RepeatState::new(repeat));
+                    value
+                }
+            },
+            _ => {
+                state.repeat = None;
+                value
+            }
+        }
+    }
+}
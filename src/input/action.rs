@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+
+use crate::{
+    input::binding::{InputBinding, InputDevice},
+    AutoBinder, InputMapper,
+};
+
+/// What kind of value an action produces.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ActionKind {
+    /// Pressed or not, e.g. jump.
+    #[default]
+    Button,
+    /// A single analog value, e.g. throttle.
+    Axis,
+    /// Two analog values, e.g. look or move.
+    DualAxis,
+}
+
+/// Metadata of an action, used by remapping menus and save-file validation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionInfo {
+    /// Display name or localization key. Empty means the action name is displayed.
+    pub display_name: String,
+    /// Groups actions in menus, e.g. "Movement" or "Combat".
+    pub category: Option<String>,
+    pub description: Option<String>,
+    /// Whether the user may change the action's bindings.
+    pub rebindable: bool,
+    /// Devices the action may be bound to.
+    pub devices: Vec<InputDevice>,
+    pub kind: ActionKind,
+}
+
+impl Default for ActionInfo {
+    fn default() -> Self {
+        ActionInfo {
+            display_name: String::new(),
+            category: None,
+            description: None,
+            rebindable: true,
+            devices: vec![
+                InputDevice::Keyboard,
+                InputDevice::Mouse,
+                InputDevice::Gamepad,
+            ],
+            kind: ActionKind::Button,
+        }
+    }
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Registers metadata of an action. Actions are shared between scenarios,
+    /// so the metadata applies to the action in every scenario.
+    pub fn register_action(&mut self, action: impl ToString, info: ActionInfo) -> &mut Self {
+        self.action_info.bind(action.to_string(), info);
+        self
+    }
+    pub fn action_info(&self, action: &str) -> Option<&ActionInfo> {
+        self.action_info.get(action)
+    }
+    /// The registered display name, or the action name itself.
+    pub fn action_display_name<'a>(&'a self, action: &'a str) -> &'a str {
+        match self.action_info(action) {
+            Some(info) if !info.display_name.is_empty() => &info.display_name,
+            _ => action,
+        }
+    }
+    /// Every registered category, sorted.
+    pub fn action_categories(&self) -> Vec<String> {
+        let mut categories = self
+            .action_info
+            .values()
+            .filter_map(|info| info.category.clone())
+            .collect::<Vec<_>>();
+        categories.sort();
+        categories.dedup();
+        categories
+    }
+    /// Every registered action in the category, sorted.
+    pub fn category_actions(&self, category: &str) -> Vec<String> {
+        let mut actions = self
+            .action_info
+            .iter()
+            .filter(|(_, info)| info.category.as_deref() == Some(category))
+            .map(|(action, _)| action.to_owned())
+            .collect::<Vec<_>>();
+        actions.sort();
+        actions
+    }
+    /// Whether the user may bind the input to the action, according to its metadata.
+    /// Actions without metadata accept every input.
+    pub fn can_rebind(&self, action: &str, input: &InputBinding) -> bool {
+        match self.action_info(action) {
+            Some(info) => info.rebindable && info.devices.contains(&input.device()),
+            None => true,
+        }
+    }
+}
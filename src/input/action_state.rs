@@ -0,0 +1,133 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    input::{accessibility::ResolveState, binding::InputSource},
+    AutoBinder, InputMapper,
+};
+
+/// Devices that drive an entity's [`ActionState`], using the [`InputMapper`]'s bindings
+/// of the current scenario. Entities without it, e.g. AI-controlled characters,
+/// keep whatever is written to their `ActionState`.
+#[derive(Component, Debug, Default, Clone, PartialEq)]
+pub struct InputMap {
+    /// Whether the keyboard and mouse drive this entity.
+    pub keyboard_mouse: bool,
+    pub gamepad: Option<Gamepad>,
+}
+
+impl InputMap {
+    pub fn keyboard_mouse() -> Self {
+        InputMap {
+            keyboard_mouse: true,
+            gamepad: None,
+        }
+    }
+    pub fn gamepad(gamepad: Gamepad) -> Self {
+        InputMap {
+            keyboard_mouse: false,
+            gamepad: Some(gamepad),
+        }
+    }
+}
+
+/// Action values of a single entity, e.g. one of several local players.
+#[derive(Component, Debug, Default, Clone, PartialEq)]
+pub struct ActionState {
+    pub(crate) values: HashMap<String, f32>,
+    pub(crate) previous_values: HashMap<String, f32>,
+    pub(crate) resolve_state: HashMap<String, ResolveState>,
+}
+
+impl ActionState {
+    pub fn value(&self, action: &str) -> f32 {
+        self.values.get(action).copied().unwrap_or(0.)
+    }
+    pub fn pressed(&self, action: &str) -> bool {
+        self.value(action) > 0.
+    }
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.previous_value(action) == 0. && self.value(action) > 0.
+    }
+    pub fn just_released(&self, action: &str) -> bool {
+        self.previous_value(action) > 0. && self.value(action) == 0.
+    }
+    /// Sets the action's value, e.g. from an AI agent. Entities with an [`InputMap`]
+    /// get their values from devices again on the next update.
+    pub fn set_value(&mut self, action: impl ToString, value: f32) -> &mut Self {
+        self.values.bind(action.to_string(), value);
+        self
+    }
+    pub fn press(&mut self, action: impl ToString) -> &mut Self {
+        self.set_value(action, 1.)
+    }
+    pub fn release(&mut self, action: impl ToString) -> &mut Self {
+        self.set_value(action, 0.)
+    }
+    fn previous_value(&self, action: &str) -> f32 {
+        self.previous_values.get(action).copied().unwrap_or(0.)
+    }
+
+    /// Starts a new frame, what was current becomes previous.
+    pub(crate) fn tick_system(mut states: Query<&mut ActionState>) {
+        for mut state in states.iter_mut() {
+            let state = &mut *state;
+            state.previous_values.clone_from(&state.values);
+        }
+    }
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Resolves each entity's values from the inputs of its devices, the same way the
+    /// mapper's own values are: aggregation, thresholds, press modes and auto repeat.
+    pub(crate) fn action_state_system(
+        im: Res<InputMapper<T>>,
+        state: Res<State<T>>,
+        time: Res<Time>,
+        mut entities: Query<(&InputMap, &mut ActionState)>,
+    ) {
+        let current_state = *state.get();
+        for (map, mut action_state) in entities.iter_mut() {
+            let action_state = &mut *action_state;
+            let drives = |source: &InputSource| match source.gamepad {
+                Some(gamepad) => map.gamepad == Some(gamepad),
+                None => map.keyboard_mouse,
+            };
+            let mut raw_values: HashMap<String, f32> = action_state
+                .resolve_state
+                .keys()
+                .map(|action| (action.to_owned(), 0.))
+                .collect();
+            for ((st, action), sources) in im.input_values.iter() {
+                if st != &current_state {
+                    continue;
+                }
+                let values: Vec<f32> = sources
+                    .iter()
+                    .filter(|(source, _)| drives(source))
+                    .map(|(_, value)| *value)
+                    .collect();
+                let last_active = im
+                    .last_active_input
+                    .get(&(*st, action.to_owned()))
+                    .filter(|source| drives(source))
+                    .and_then(|source| sources.get(source))
+                    .copied();
+                let value = im.aggregation(action).combine(&values, last_active);
+                raw_values.bind(action.to_owned(), value);
+            }
+            let mut values = HashMap::default();
+            for (action, raw_value) in raw_values {
+                let resolve = action_state
+                    .resolve_state
+                    .entry(action.to_owned())
+                    .or_default();
+                let value = im.resolve_value(&action, raw_value, time.delta(), resolve);
+                values.bind(action, value);
+            }
+            action_state.values = values;
+        }
+    }
+}
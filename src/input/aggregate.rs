@@ -0,0 +1,99 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    input::binding::{InputBinding, InputSource},
+    AutoBinder, InputMapper,
+};
+
+/// How the values of several inputs bound to one action combine into the action's value.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Aggregation {
+    /// The largest value. Holding Space and South both keep "jump" held until both are released.
+    #[default]
+    Max,
+    /// All values added together, e.g. mouse motion and a stick driving the same camera axis.
+    Sum,
+    /// The value of the input pressed last, falling back to the largest value when it is released.
+    LastActive,
+}
+
+impl Aggregation {
+    /// Combines input values, `last_active` being the value of the input pressed last.
+    pub(crate) fn combine(&self, values: &[f32], last_active: Option<f32>) -> f32 {
+        let max = values.iter().copied().fold(0., f32::max);
+        match self {
+            Aggregation::Max => max,
+            Aggregation::Sum => values.iter().sum(),
+            Aggregation::LastActive => last_active.filter(|value| *value > 0.).unwrap_or(max),
+        }
+    }
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Sets how the action's inputs combine in every scenario.
+    pub fn set_aggregation(
+        &mut self,
+        action: impl ToString,
+        aggregation: Aggregation,
+    ) -> &mut Self {
+        let action = action.to_string();
+        if aggregation == Aggregation::Max {
+            self.aggregation.unbind(action);
+        } else {
+            self.aggregation.bind(action, aggregation);
+        }
+        self
+    }
+    pub fn aggregation(&self, action: &str) -> Aggregation {
+        self.aggregation.get(action).copied().unwrap_or_default()
+    }
+    /// The value a single input currently contributes to the action, zero if it isn't held.
+    /// For gamepad inputs, the largest value among the connected gamepads.
+    pub fn input_value(&self, state: T, action: &str, input: &InputBinding) -> f32 {
+        self.input_values
+            .get(&(state, action.to_owned()))
+            .map(|values| {
+                values
+                    .iter()
+                    .filter(|(source, _)| source.input == *input)
+                    .map(|(_, value)| *value)
+                    .fold(0., f32::max)
+            })
+            .unwrap_or(0.)
+    }
+    /// Drops the values of every input matching `filter` from the action and aggregates the rest.
+    pub(crate) fn remove_input_values(
+        &mut self,
+        key: &(T, String),
+        filter: impl Fn(&InputSource) -> bool,
+    ) {
+        let Some(values) = self.input_values.get_mut(key) else {
+            return;
+        };
+        values.retain(|source, _| !filter(source));
+        if self.last_active_input.get(key).is_some_and(&filter) {
+            self.last_active_input.remove(key);
+        }
+        self.input_threshold_pressed
+            .retain(|(st, source)| st != &key.0 || !filter(source));
+        self.aggregate_input_values(key.to_owned());
+    }
+
+    /// Writes the combined value of the action's inputs as its raw value.
+    pub(crate) fn aggregate_input_values(&mut self, key: (T, String)) {
+        let empty = HashMap::default();
+        let values = self.input_values.get(&key).unwrap_or(&empty);
+        let last_active = self
+            .last_active_input
+            .get(&key)
+            .and_then(|source| values.get(source))
+            .copied();
+        let values: Vec<f32> = values.values().copied().collect();
+        let value = self.aggregation(&key.1).combine(&values, last_active);
+        self.raw_action_value.bind(key, value);
+    }
+}
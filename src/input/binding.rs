@@ -0,0 +1,253 @@
+use bevy::prelude::*;
+
+use crate::{
+    input::{gamepad::GamepadAxis, mouse::MouseAxis, semantic::SemanticButton},
+    AutoBinder, InputMapper,
+};
+
+/// Any input that can be bound to an action.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputBinding {
+    Keyboard(KeyCode),
+    ScanCode(ScanCode),
+    MouseButton(MouseButton),
+    MouseAxis(MouseAxis),
+    GamepadButton(GamepadButtonType),
+    GamepadAxis(GamepadAxis),
+    GamepadSemantic(SemanticButton),
+}
+
+/// An input, and for gamepad inputs the gamepad it was read from.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub(crate) struct InputSource {
+    pub input: InputBinding,
+    pub gamepad: Option<Gamepad>,
+}
+
+/// Device an [`InputBinding`] belongs to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputDevice {
+    Keyboard,
+    Mouse,
+    Gamepad,
+}
+
+/// Every input bound to an action, grouped by device. See [`InputMapper::scenario_bindings`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionBindings {
+    pub action: String,
+    pub keyboard: Vec<InputBinding>,
+    pub mouse: Vec<InputBinding>,
+    pub gamepad: Vec<InputBinding>,
+}
+
+impl ActionBindings {
+    /// Inputs of every device, keyboard first, gamepad last.
+    pub fn inputs(&self) -> impl Iterator<Item = &InputBinding> {
+        self.keyboard
+            .iter()
+            .chain(self.mouse.iter())
+            .chain(self.gamepad.iter())
+    }
+}
+
+impl InputBinding {
+    pub fn device(&self) -> InputDevice {
+        match self {
+            InputBinding::Keyboard(_) | InputBinding::ScanCode(_) => InputDevice::Keyboard,
+            InputBinding::MouseButton(_) | InputBinding::MouseAxis(_) => InputDevice::Mouse,
+            InputBinding::GamepadButton(_)
+            | InputBinding::GamepadAxis(_)
+            | InputBinding::GamepadSemantic(_) => InputDevice::Gamepad,
+        }
+    }
+    /// Not every Bevy input type is `Ord`, so listings are sorted by this instead.
+    pub(crate) fn sort_key(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Binds any kind of input to an action.
+    pub fn bind(&mut self, scenario: T, input: InputBinding, action: impl ToString) -> &mut Self {
+        match input {
+            InputBinding::Keyboard(key) => self.bind_keyboard_key_press(scenario, key, action),
+            InputBinding::ScanCode(scan_code) => {
+                self.bind_keyboard_scancode_press(scenario, scan_code, action)
+            }
+            InputBinding::MouseButton(button) => {
+                self.bind_mouse_button_press(scenario, button, action)
+            }
+            InputBinding::MouseAxis(axis) => self.bind_mouse_axis_move(scenario, axis, action),
+            InputBinding::GamepadButton(button) => {
+                self.bind_gamepad_button_press(scenario, button, action)
+            }
+            InputBinding::GamepadAxis(axis) => self.bind_gamepad_axis_move(scenario, axis, action),
+            InputBinding::GamepadSemantic(button) => {
+                self.bind_gamepad_semantic_press(scenario, button, action)
+            }
+        }
+    }
+    /// Returns the action an input is bound to in the scenario.
+    pub fn bound_action(&self, scenario: T, input: &InputBinding) -> Option<&String> {
+        match *input {
+            InputBinding::Keyboard(key) => self.keyboard_binding.get(&(scenario, key)),
+            InputBinding::ScanCode(scan_code) => self.scancode_binding.get(&(scenario, scan_code)),
+            InputBinding::MouseButton(button) => self.mouse_button_binding.get(&(scenario, button)),
+            InputBinding::MouseAxis(axis) => self.mouse_axis_binding.get(&(scenario, axis)),
+            InputBinding::GamepadButton(button) => {
+                self.gamepad_button_binding.get(&(scenario, button))
+            }
+            InputBinding::GamepadAxis(axis) => self.gamepad_axis_binding.get(&(scenario, axis)),
+            InputBinding::GamepadSemantic(button) => self.semantic_binding.get(&(scenario, button)),
+        }
+    }
+    /// Every input bound to the action in the scenario.
+    pub fn bound_inputs(&self, scenario: T, action: &str) -> Vec<InputBinding> {
+        self.bindings()
+            .into_iter()
+            .filter(|(sc, _, act)| sc == &scenario && act == action)
+            .map(|(_, input, _)| input)
+            .collect()
+    }
+    /// Every action of the scenario with its bound inputs, sorted by action name.
+    /// Inputs within a device are sorted as well, so the result is stable between calls.
+    pub fn scenario_bindings(&self, scenario: T) -> Vec<ActionBindings> {
+        let mut bindings = self
+            .bindings()
+            .into_iter()
+            .filter(|(sc, _, _)| sc == &scenario)
+            .collect::<Vec<_>>();
+        bindings.sort_by_cached_key(|(_, input, action)| (action.to_owned(), input.sort_key()));
+        let mut actions: Vec<ActionBindings> = vec![];
+        for (_, input, action) in bindings {
+            if actions.last().map(|last| &last.action) != Some(&action) {
+                actions.push(ActionBindings {
+                    action,
+                    keyboard: vec![],
+                    mouse: vec![],
+                    gamepad: vec![],
+                });
+            }
+            let last = actions.last_mut().unwrap();
+            match input.device() {
+                InputDevice::Keyboard => last.keyboard.push(input),
+                InputDevice::Mouse => last.mouse.push(input),
+                InputDevice::Gamepad => last.gamepad.push(input),
+            }
+        }
+        actions
+    }
+    /// Every `(scenario, action)` the input is bound to, sorted by action name.
+    pub fn input_actions(&self, input: &InputBinding) -> Vec<(T, String)> {
+        let mut actions = self
+            .bindings()
+            .into_iter()
+            .filter(|(_, bound, _)| bound == input)
+            .map(|(sc, _, action)| (sc, action))
+            .collect::<Vec<_>>();
+        actions.sort_by_cached_key(|(sc, action)| (action.to_owned(), format!("{sc:?}")));
+        actions
+    }
+    /// Removes every binding of the action in the scenario, releasing it if held.
+    pub fn unbind_action(&mut self, scenario: T, action: &str) -> &mut Self {
+        for input in self.bound_inputs(scenario, action) {
+            self.remove_binding(scenario, &input);
+        }
+        self.pointer_binding.unbind((scenario, action.to_owned()));
+        self.release(scenario, action);
+        self
+    }
+    /// Removes the input's binding in the scenario, releasing its action if held.
+    pub fn unbind_input(&mut self, scenario: T, input: InputBinding) -> &mut Self {
+        if let Some(action) = self.bound_action(scenario, &input).cloned() {
+            self.remove_binding(scenario, &input);
+            self.release(scenario, &action);
+        }
+        self
+    }
+    /// Removes every binding in the scenario, releasing its held actions.
+    pub fn clear_scenario(&mut self, scenario: T) -> &mut Self {
+        for (sc, input, action) in self.bindings() {
+            if sc == scenario {
+                self.remove_binding(scenario, &input);
+                self.release(scenario, &action);
+            }
+        }
+        self.pointer_binding.retain(|(sc, _), _| sc != &scenario);
+        self
+    }
+    /// Every binding, as `(scenario, input, action)`.
+    pub(crate) fn bindings(&self) -> Vec<(T, InputBinding, String)> {
+        let mut bindings = vec![];
+        for ((sc, key), action) in self.keyboard_binding.iter() {
+            bindings.push((*sc, InputBinding::Keyboard(*key), action.to_owned()));
+        }
+        for ((sc, scan_code), action) in self.scancode_binding.iter() {
+            bindings.push((*sc, InputBinding::ScanCode(*scan_code), action.to_owned()));
+        }
+        for ((sc, button), action) in self.mouse_button_binding.iter() {
+            bindings.push((*sc, InputBinding::MouseButton(*button), action.to_owned()));
+        }
+        for ((sc, axis), action) in self.mouse_axis_binding.iter() {
+            bindings.push((*sc, InputBinding::MouseAxis(*axis), action.to_owned()));
+        }
+        for ((sc, button), action) in self.gamepad_button_binding.iter() {
+            bindings.push((*sc, InputBinding::GamepadButton(*button), action.to_owned()));
+        }
+        for ((sc, axis), action) in self.gamepad_axis_binding.iter() {
+            bindings.push((*sc, InputBinding::GamepadAxis(*axis), action.to_owned()));
+        }
+        for ((sc, button), action) in self.semantic_binding.iter() {
+            bindings.push((
+                *sc,
+                InputBinding::GamepadSemantic(*button),
+                action.to_owned(),
+            ));
+        }
+        bindings
+    }
+    pub(crate) fn remove_binding(&mut self, scenario: T, input: &InputBinding) -> &mut Self {
+        match *input {
+            InputBinding::Keyboard(key) => {
+                self.keyboard_binding.unbind((scenario, key));
+            }
+            InputBinding::ScanCode(scan_code) => {
+                self.scancode_binding.unbind((scenario, scan_code));
+            }
+            InputBinding::MouseButton(button) => {
+                self.mouse_button_binding.unbind((scenario, button));
+            }
+            InputBinding::MouseAxis(axis) => {
+                self.mouse_axis_binding.unbind((scenario, axis));
+            }
+            InputBinding::GamepadButton(button) => {
+                self.gamepad_button_binding.unbind((scenario, button));
+            }
+            InputBinding::GamepadAxis(axis) => {
+                self.gamepad_axis_binding.unbind((scenario, axis));
+            }
+            InputBinding::GamepadSemantic(button) => {
+                self.semantic_binding.unbind((scenario, button));
+            }
+        }
+        let keys: Vec<(T, String)> = self
+            .input_values
+            .keys()
+            .filter(|(st, _)| st == &scenario)
+            .cloned()
+            .collect();
+        for key in keys.iter() {
+            self.remove_input_values(key, |source| source.input == *input);
+        }
+        // The threshold was set up for this binding, it shouldn't carry over to the next one.
+        self.clear_input_threshold(scenario, *input);
+        self
+    }
+}
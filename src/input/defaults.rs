@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+
+use crate::{input::binding::InputBinding, InputMapper};
+
+/// The user's changes on top of the default bindings.
+/// Saving this instead of every binding keeps later changes to the defaults effective.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BindingOverrides<T>
+where
+    T: Copy + States,
+{
+    /// Bindings that are not among the defaults, as `(scenario, input, action)`.
+    pub added: Vec<(T, InputBinding, String)>,
+    /// Default bindings that were removed or rebound, as `(scenario, input, action)`.
+    pub removed: Vec<(T, InputBinding, String)>,
+}
+
+impl<T> Default for BindingOverrides<T>
+where
+    T: Copy + States,
+{
+    fn default() -> Self {
+        BindingOverrides {
+            added: vec![],
+            removed: vec![],
+        }
+    }
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Remembers the current bindings and input thresholds as defaults, everything bound
+    /// afterwards is a user override. Call it once the designer-authored bindings are in place.
+    pub fn set_defaults(&mut self) -> &mut Self {
+        self.default_bindings = self.bindings();
+        self.default_input_thresholds = self.input_thresholds.clone();
+        self
+    }
+    /// Replaces every binding with the defaults, releasing held actions.
+    pub fn reset_to_defaults(&mut self) -> &mut Self {
+        for (sc, input, action) in self.bindings() {
+            self.remove_binding(sc, &input);
+            self.release(sc, &action);
+        }
+        for (sc, input, action) in self.default_bindings.clone() {
+            self.bind(sc, input, action);
+            self.restore_default_threshold(sc, input);
+        }
+        self
+    }
+    /// Replaces the scenario's bindings with the defaults, releasing held actions.
+    pub fn reset_scenario_to_defaults(&mut self, scenario: T) -> &mut Self {
+        self.clear_scenario(scenario);
+        for (sc, input, action) in self.default_bindings.clone() {
+            if sc == scenario {
+                self.bind(sc, input, action);
+                self.restore_default_threshold(sc, input);
+            }
+        }
+        self
+    }
+    /// Replaces the action's bindings with the defaults, releasing it if held.
+    /// A default input that the user moved to another action is taken back.
+    pub fn reset_action_to_defaults(&mut self, scenario: T, action: &str) -> &mut Self {
+        self.unbind_action(scenario, action);
+        for (sc, input, act) in self.default_bindings.clone() {
+            if sc == scenario && act == action {
+                if let Some(other) = self.bound_action(sc, &input).cloned() {
+                    self.remove_binding(sc, &input);
+                    self.release(sc, &other);
+                }
+                self.bind(sc, input, act);
+                self.restore_default_threshold(sc, input);
+            }
+        }
+        self
+    }
+    fn restore_default_threshold(&mut self, scenario: T, input: InputBinding) {
+        if let Some(threshold) = self
+            .default_input_thresholds
+            .get(&(scenario, input))
+            .copied()
+        {
+            self.set_input_threshold(scenario, input, threshold);
+        }
+    }
+    /// Whether the action's bindings differ from the defaults.
+    pub fn is_action_overridden(&self, scenario: T, action: &str) -> bool {
+        let overrides = self.user_overrides();
+        overrides
+            .added
+            .iter()
+            .chain(overrides.removed.iter())
+            .any(|(sc, _, act)| sc == &scenario && act == action)
+    }
+    /// The difference between the current bindings and the defaults.
+    pub fn user_overrides(&self) -> BindingOverrides<T> {
+        let current = self.bindings();
+        BindingOverrides {
+            added: current
+                .iter()
+                .filter(|binding| !self.default_bindings.contains(binding))
+                .cloned()
+                .collect(),
+            removed: self
+                .default_bindings
+                .iter()
+                .filter(|binding| !current.contains(binding))
+                .cloned()
+                .collect(),
+        }
+    }
+    /// Resets to the defaults and applies previously saved [`InputMapper::user_overrides`].
+    /// Overrides that the actions' metadata forbids are skipped.
+    pub fn apply_overrides(&mut self, overrides: &BindingOverrides<T>) -> &mut Self {
+        self.reset_to_defaults();
+        for (sc, input, action) in overrides.removed.iter() {
+            if self.bound_action(*sc, input) == Some(action) && self.can_rebind(action, input) {
+                self.remove_binding(*sc, input);
+            }
+        }
+        for (sc, input, action) in overrides.added.iter() {
+            if self.can_rebind(action, input) {
+                self.bind(*sc, *input, action);
+            }
+        }
+        self
+    }
+}
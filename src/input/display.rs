@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+
+use crate::{
+    input::{binding::InputBinding, gamepad::GamepadAxis, mouse::MouseAxis},
+    InputMapper,
+};
+
+/// Controller family, decides how gamepad buttons are named.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadFamily {
+    /// Position based names, e.g. "South".
+    #[default]
+    Generic,
+    Xbox,
+    PlayStation,
+    Nintendo,
+}
+
+impl GamepadFamily {
+    fn glyph_prefix(&self) -> &'static str {
+        match self {
+            GamepadFamily::Generic => "gamepad",
+            GamepadFamily::Xbox => "xbox",
+            GamepadFamily::PlayStation => "ps",
+            GamepadFamily::Nintendo => "switch",
+        }
+    }
+}
+
+/// Human-readable name of an input, and a stable identifier to look up its glyph with.
+/// Glyph identifiers are lowercase snake case, e.g. `key_space`, `mouse_left` or `xbox_a`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputLabel {
+    pub name: String,
+    pub glyph: String,
+}
+
+impl InputLabel {
+    fn new(name: impl ToString, glyph: impl ToString) -> Self {
+        InputLabel {
+            name: name.to_string(),
+            glyph: glyph.to_string(),
+        }
+    }
+}
+
+fn key_label(key: KeyCode) -> InputLabel {
+    let name = match key {
+        KeyCode::Key1 => "1".to_owned(),
+        KeyCode::Key2 => "2".to_owned(),
+        KeyCode::Key3 => "3".to_owned(),
+        KeyCode::Key4 => "4".to_owned(),
+        KeyCode::Key5 => "5".to_owned(),
+        KeyCode::Key6 => "6".to_owned(),
+        KeyCode::Key7 => "7".to_owned(),
+        KeyCode::Key8 => "8".to_owned(),
+        KeyCode::Key9 => "9".to_owned(),
+        KeyCode::Key0 => "0".to_owned(),
+        KeyCode::Escape => "Esc".to_owned(),
+        KeyCode::Return => "Enter".to_owned(),
+        KeyCode::Back => "Backspace".to_owned(),
+        KeyCode::Up => "Up Arrow".to_owned(),
+        KeyCode::Down => "Down Arrow".to_owned(),
+        KeyCode::Left => "Left Arrow".to_owned(),
+        KeyCode::Right => "Right Arrow".to_owned(),
+        KeyCode::ShiftLeft => "Left Shift".to_owned(),
+        KeyCode::ShiftRight => "Right Shift".to_owned(),
+        KeyCode::ControlLeft => "Left Ctrl".to_owned(),
+        KeyCode::ControlRight => "Right Ctrl".to_owned(),
+        KeyCode::AltLeft => "Left Alt".to_owned(),
+        KeyCode::AltRight => "Right Alt".to_owned(),
+        KeyCode::SuperLeft => "Left Super".to_owned(),
+        KeyCode::SuperRight => "Right Super".to_owned(),
+        KeyCode::Capital => "Caps Lock".to_owned(),
+        KeyCode::PageUp => "Page Up".to_owned(),
+        KeyCode::PageDown => "Page Down".to_owned(),
+        KeyCode::NumpadEnter => "Numpad Enter".to_owned(),
+        other => format!("{other:?}"),
+    };
+    InputLabel::new(&name, format!("key_{}", glyph_case(&name)))
+}
+
+fn mouse_button_label(button: MouseButton) -> InputLabel {
+    match button {
+        MouseButton::Left => InputLabel::new("Left Click", "mouse_left"),
+        MouseButton::Right => InputLabel::new("Right Click", "mouse_right"),
+        MouseButton::Middle => InputLabel::new("Middle Click", "mouse_middle"),
+        MouseButton::Other(n) => InputLabel::new(format!("Mouse {n}"), format!("mouse_{n}")),
+    }
+}
+
+fn mouse_axis_label(axis: MouseAxis) -> InputLabel {
+    match axis {
+        MouseAxis::PositiveX => InputLabel::new("Mouse Right", "mouse_move_right"),
+        MouseAxis::NegativeX => InputLabel::new("Mouse Left", "mouse_move_left"),
+        MouseAxis::PositiveY => InputLabel::new("Mouse Down", "mouse_move_down"),
+        MouseAxis::NegativeY => InputLabel::new("Mouse Up", "mouse_move_up"),
+    }
+}
+
+fn gamepad_button_label(button: GamepadButtonType, family: GamepadFamily) -> InputLabel {
+    use GamepadFamily::*;
+    // Names for Generic, Xbox, PlayStation and Nintendo, in that order.
+    let names = match button {
+        GamepadButtonType::South => ["South", "A", "Cross", "B"],
+        GamepadButtonType::East => ["East", "B", "Circle", "A"],
+        GamepadButtonType::North => ["North", "Y", "Triangle", "X"],
+        GamepadButtonType::West => ["West", "X", "Square", "Y"],
+        GamepadButtonType::C => ["C"; 4],
+        GamepadButtonType::Z => ["Z"; 4],
+        GamepadButtonType::LeftTrigger => ["Left Bumper", "LB", "L1", "L"],
+        GamepadButtonType::LeftTrigger2 => ["Left Trigger", "LT", "L2", "ZL"],
+        GamepadButtonType::RightTrigger => ["Right Bumper", "RB", "R1", "R"],
+        GamepadButtonType::RightTrigger2 => ["Right Trigger", "RT", "R2", "ZR"],
+        GamepadButtonType::Select => ["Select", "View", "Share", "Minus"],
+        GamepadButtonType::Start => ["Start", "Menu", "Options", "Plus"],
+        GamepadButtonType::Mode => ["Mode", "Xbox", "PS", "Home"],
+        GamepadButtonType::LeftThumb => ["Left Stick Press", "LS", "L3", "Left Stick Press"],
+        GamepadButtonType::RightThumb => ["Right Stick Press", "RS", "R3", "Right Stick Press"],
+        GamepadButtonType::DPadUp => ["D-Pad Up"; 4],
+        GamepadButtonType::DPadDown => ["D-Pad Down"; 4],
+        GamepadButtonType::DPadLeft => ["D-Pad Left"; 4],
+        GamepadButtonType::DPadRight => ["D-Pad Right"; 4],
+        GamepadButtonType::Other(n) => {
+            return InputLabel::new(
+                format!("Button {n}"),
+                format!("{}_button_{n}", family.glyph_prefix()),
+            )
+        }
+    };
+    let name = match family {
+        Generic => names[0],
+        Xbox => names[1],
+        PlayStation => names[2],
+        Nintendo => names[3],
+    };
+    InputLabel::new(
+        name,
+        format!("{}_{}", family.glyph_prefix(), glyph_case(name)),
+    )
+}
+
+fn gamepad_axis_label(axis: GamepadAxis, family: GamepadFamily) -> InputLabel {
+    let name = match axis {
+        GamepadAxis::PositiveLeftStickX => "Left Stick Right".to_owned(),
+        GamepadAxis::NegativeLeftStickX => "Left Stick Left".to_owned(),
+        GamepadAxis::PositiveLeftStickY => "Left Stick Up".to_owned(),
+        GamepadAxis::NegativeLeftStickY => "Left Stick Down".to_owned(),
+        GamepadAxis::PositiveRightStickX => "Right Stick Right".to_owned(),
+        GamepadAxis::NegativeRightStickX => "Right Stick Left".to_owned(),
+        GamepadAxis::PositiveRightStickY => "Right Stick Up".to_owned(),
+        GamepadAxis::NegativeRightStickY => "Right Stick Down".to_owned(),
+        GamepadAxis::PositiveLeftZ => "Left Z Positive".to_owned(),
+        GamepadAxis::NegativeLeftZ => "Left Z Negative".to_owned(),
+        GamepadAxis::PositiveRightZ => "Right Z Positive".to_owned(),
+        GamepadAxis::NegativeRightZ => "Right Z Negative".to_owned(),
+        GamepadAxis::PositiveOtherAxis(n) => format!("Axis {n} Positive"),
+        GamepadAxis::NegativeOtherAxis(n) => format!("Axis {n} Negative"),
+    };
+    InputLabel::new(
+        &name,
+        format!("{}_{}", family.glyph_prefix(), glyph_case(&name)),
+    )
+}
+
+/// "Left Stick Right" -> "left_stick_right", "NumpadAdd" -> "numpad_add".
+fn glyph_case(name: &str) -> String {
+    let mut glyph = String::new();
+    let mut previous_lowercase = false;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase() && previous_lowercase {
+                glyph.push('_');
+            }
+            glyph.push(c.to_ascii_lowercase());
+            previous_lowercase = c.is_ascii_lowercase() || c.is_ascii_digit();
+        } else {
+            if !glyph.is_empty() && !glyph.ends_with('_') {
+                glyph.push('_');
+            }
+            previous_lowercase = false;
+        }
+    }
+    glyph.trim_end_matches('_').to_owned()
+}
+
+impl InputBinding {
+    /// Name and glyph identifier of the input. Gamepad buttons are named after the family.
+    /// Physical keys can't be named without the keyboard layout, see [`InputMapper::input_label`].
+    pub fn label(&self, family: GamepadFamily) -> InputLabel {
+        match *self {
+            InputBinding::Keyboard(key) => key_label(key),
+            InputBinding::ScanCode(ScanCode(code)) => {
+                InputLabel::new(format!("Scan Code {code}"), format!("scancode_{code}"))
+            }
+            InputBinding::MouseButton(button) => mouse_button_label(button),
+            InputBinding::MouseAxis(axis) => mouse_axis_label(axis),
+            InputBinding::GamepadButton(button) => gamepad_button_label(button, family),
+            InputBinding::GamepadAxis(axis) => gamepad_axis_label(axis, family),
+            InputBinding::GamepadSemantic(button) => {
+                gamepad_button_label(button.default_button(family), family)
+            }
+        }
+    }
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Sets the controller family gamepad buttons are named after.
+    pub fn set_gamepad_family(&mut self, family: GamepadFamily) -> &mut Self {
+        self.gamepad_family = family;
+        self
+    }
+    pub fn gamepad_family(&self) -> GamepadFamily {
+        self.gamepad_family
+    }
+    /// Name and glyph identifier of the input, for "Press [A] to jump" prompts.
+    /// Physical keys are named after the key the user's layout produces, once known.
+    pub fn input_label(&self, input: &InputBinding) -> InputLabel {
+        match *input {
+            InputBinding::ScanCode(scan_code) => match self.scancode_label(scan_code) {
+                Some(key) => key_label(key),
+                None => input.label(self.gamepad_family),
+            },
+            InputBinding::GamepadSemantic(button) => {
+                gamepad_button_label(self.resolve_semantic_button(button), self.gamepad_family)
+            }
+            _ => input.label(self.gamepad_family),
+        }
+    }
+    /// Labels of every input bound to the action in the scenario.
+    pub fn action_input_labels(&self, scenario: T, action: &str) -> Vec<InputLabel> {
+        let mut inputs = self.bound_inputs(scenario, action);
+        inputs.sort_by_cached_key(|input| input.sort_key());
+        inputs.iter().map(|input| self.input_label(input)).collect()
+    }
+}
@@ -1,6 +1,15 @@
 use bevy::prelude::*;
 
-use crate::{AutoBinder, InputMapper};
+use crate::{input::binding::InputBinding, AutoBinder, InputMapper};
+
+/// Systems that read devices into the [`InputMapper`].
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct DeviceInputSystem;
+
+/// Resolves the action values of the frame and sends the action events, once the devices
+/// were read. Systems that read the events or the [`InputMapper`]'s values should run after it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionEventSystem;
 
 #[derive(Event)]
 pub struct InputActionActive(pub String, pub f32);
@@ -11,6 +20,36 @@ pub struct InputActionContinuing(pub String, pub f32);
 #[derive(Event)]
 pub struct InputActionFinished(pub String);
 
+/// Sent when [`InputMapper::start_rebind`] captured an input and bound it.
+#[derive(Event)]
+pub struct RebindCompleted<T>
+where
+    T: Copy + States,
+{
+    pub scenario: T,
+    pub action: String,
+    pub input: InputBinding,
+    /// The action this input was bound to before, which lost the binding.
+    pub conflict: Option<String>,
+}
+/// Sent when listening for [`InputMapper::start_rebind`] was cancelled with a cancel key.
+#[derive(Event)]
+pub struct RebindCancelled<T>
+where
+    T: Copy + States,
+{
+    pub scenario: T,
+    pub action: String,
+}
+/// Sent when a gamepad disconnects, after the actions it was driving were released.
+/// Games can use it to pause and show a reconnect prompt.
+#[derive(Event)]
+pub struct PlayerDeviceLost {
+    pub gamepad: Gamepad,
+    /// Actions the gamepad was holding when it disconnected, in any scenario.
+    pub actions: Vec<String>,
+}
+
 impl<T> InputMapper<T>
 where
     T: Copy + States,
@@ -21,7 +60,11 @@ where
         mut action_started: EventWriter<InputActionStarted>,
         mut action_continuing: EventWriter<InputActionContinuing>,
         mut action_finished: EventWriter<InputActionFinished>,
+        time: Res<Time>,
     ) {
+        im.resolve_action_values(time.delta());
+        im.replay_frame();
+        im.started_actions.clear();
         let curr = im.action_value.clone();
         for (action, value) in curr.iter() {
             if im.is_active(action.0, &action.1) {
@@ -29,6 +72,7 @@ where
             }
             if im.is_started(action.0, &action.1) {
                 action_started.send(InputActionStarted(action.1.to_owned(), *value));
+                im.started_actions.push(action.to_owned());
             }
             if im.is_continuing(action.0, &action.1) {
                 action_continuing.send(InputActionContinuing(action.1.to_owned(), *value));
@@ -36,9 +80,11 @@ where
             if im.is_finished(action.0, &action.1) {
                 action_finished.send(InputActionFinished(action.1.to_owned()));
             }
+            im.latch_fixed(action.0, &action.1, *value);
         }
         for (action, value) in curr.iter() {
             im.previous_action_value.bind(action.to_owned(), *value);
         }
+        im.record_frame();
     }
 }
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use crate::InputMapper;
+
+/// Systems in [`FixedUpdate`] that read [`InputMapper::fixed_state`] should run after this set.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct FixedInputSystem;
+
+/// An action as seen by the current [`FixedUpdate`] tick.
+/// Presses and releases since the previous tick are latched, so every tap is seen by
+/// exactly one tick, even when it starts and finishes between two ticks.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FixedActionState {
+    /// The current value, or the highest value since the previous tick for a tap
+    /// that already finished.
+    pub value: f32,
+    pub just_pressed: bool,
+    pub just_released: bool,
+}
+
+impl FixedActionState {
+    pub fn pressed(&self) -> bool {
+        self.value > 0.
+    }
+}
+
+/// What happened to an action since the previous fixed tick.
+#[derive(Default, Clone, Copy)]
+pub(crate) struct FixedLatch {
+    pressed: bool,
+    released: bool,
+    peak: f32,
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// The action as seen by the current fixed tick. Read it from [`FixedUpdate`] systems
+    /// that run after [`FixedInputSystem`].
+    pub fn fixed_state(&self, scenario: T, action: &str) -> FixedActionState {
+        self.fixed_state
+            .get(&(scenario, action.to_owned()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Remembers the action's transitions of this frame until the next fixed tick.
+    pub(crate) fn latch_fixed(&mut self, state: T, action: &str, value: f32) {
+        let started = self.is_started(state, action);
+        let finished = self.is_finished(state, action);
+        let latch = self
+            .fixed_latch
+            .entry((state, action.to_owned()))
+            .or_default();
+        latch.pressed |= started;
+        latch.released |= finished;
+        latch.peak = latch.peak.max(value);
+    }
+
+    pub(crate) fn fixed_tick_system(mut im: ResMut<InputMapper<T>>) {
+        let latches = std::mem::take(&mut im.fixed_latch);
+        let curr = im.action_value.clone();
+        im.fixed_state.clear();
+        for (action, value) in curr.into_iter() {
+            let latch = latches.get(&action).copied().unwrap_or_default();
+            let value = if latch.pressed && value == 0. {
+                latch.peak
+            } else {
+                value
+            };
+            im.fixed_state.insert(
+                action,
+                FixedActionState {
+                    value,
+                    just_pressed: latch.pressed,
+                    just_released: latch.released,
+                },
+            );
+        }
+    }
+}
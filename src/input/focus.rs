@@ -0,0 +1,79 @@
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowFocused},
+};
+
+use crate::{
+    input::binding::{InputBinding, InputSource},
+    InputMapper,
+};
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Whether the action is released when the primary window loses focus. Enabled by default,
+    /// disable it for actions that should stay held, e.g. a toggled auto-run.
+    pub fn set_release_on_focus_loss(&mut self, action: impl ToString, enabled: bool) -> &mut Self {
+        if enabled {
+            self.keep_on_focus_loss.remove(&action.to_string());
+        } else {
+            self.keep_on_focus_loss.insert(action.to_string());
+        }
+        self
+    }
+    /// Whether the action is released when the primary window loses focus.
+    pub fn releases_on_focus_loss(&self, action: &str) -> bool {
+        !self.keep_on_focus_loss.contains(action)
+    }
+
+    /// Key and button releases that happen while unfocused never reach the app, so the
+    /// scenario's bound inputs that still look pressed are ignored until they are released,
+    /// otherwise they would press the action again.
+    pub(crate) fn focus_loss_system(
+        mut im: ResMut<InputMapper<T>>,
+        state: Res<State<T>>,
+        mut focus: EventReader<WindowFocused>,
+        primary: Query<(), With<PrimaryWindow>>,
+        keys: Res<Input<KeyCode>>,
+        scan_codes: Res<Input<ScanCode>>,
+        mouse_buttons: Res<Input<MouseButton>>,
+    ) {
+        let lost = focus
+            .iter()
+            .any(|ev| !ev.focused && primary.get(ev.window).is_ok());
+        if !lost {
+            return;
+        }
+        let current_state = *state.get();
+        let mut held = vec![];
+        for ((st, key), action) in im.keyboard_binding.iter() {
+            if st == &current_state && im.releases_on_focus_loss(action) && keys.pressed(*key) {
+                held.push(InputBinding::Keyboard(*key));
+            }
+        }
+        for ((st, scan_code), action) in im.scancode_binding.iter() {
+            if st == &current_state
+                && im.releases_on_focus_loss(action)
+                && scan_codes.pressed(*scan_code)
+            {
+                held.push(InputBinding::ScanCode(*scan_code));
+            }
+        }
+        for ((st, button), action) in im.mouse_button_binding.iter() {
+            if st == &current_state
+                && im.releases_on_focus_loss(action)
+                && mouse_buttons.pressed(*button)
+            {
+                held.push(InputBinding::MouseButton(*button));
+            }
+        }
+        im.held_inputs
+            .extend(held.into_iter().map(|input| InputSource {
+                input,
+                gamepad: None,
+            }));
+        let keep = im.keep_on_focus_loss.clone();
+        im.release_where(|(st, action)| st == &current_state && !keep.contains(action));
+    }
+}
@@ -1,12 +1,19 @@
 use bevy::{
-    input::gamepad::{GamepadAxisChangedEvent, GamepadButtonChangedEvent},
+    input::gamepad::{
+        GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnection,
+        GamepadConnectionEvent,
+    },
     prelude::*,
 };
 
-use crate::{AutoBinder, InputMapper};
+use crate::{
+    input::{binding::InputBinding, events::PlayerDeviceLost},
+    InputMapper,
+};
 
 /// Represents gamepad' analog sticks' movement.
-#[derive(Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GamepadAxis {
     /// Left Analog X axis, positive movement.
     PositiveLeftStickX,
@@ -37,6 +44,36 @@ pub enum GamepadAxis {
     NegativeOtherAxis(u8),
 }
 
+impl GamepadAxis {
+    /// Positive and negative halves of a Bevy gamepad axis.
+    pub(crate) fn halves(axis_type: GamepadAxisType) -> (GamepadAxis, GamepadAxis) {
+        match axis_type {
+            GamepadAxisType::LeftStickX => (
+                GamepadAxis::PositiveLeftStickX,
+                GamepadAxis::NegativeLeftStickX,
+            ),
+            GamepadAxisType::LeftStickY => (
+                GamepadAxis::PositiveLeftStickY,
+                GamepadAxis::NegativeLeftStickY,
+            ),
+            GamepadAxisType::LeftZ => (GamepadAxis::PositiveLeftZ, GamepadAxis::NegativeLeftZ),
+            GamepadAxisType::RightStickX => (
+                GamepadAxis::PositiveRightStickX,
+                GamepadAxis::NegativeRightStickX,
+            ),
+            GamepadAxisType::RightStickY => (
+                GamepadAxis::PositiveRightStickY,
+                GamepadAxis::NegativeRightStickY,
+            ),
+            GamepadAxisType::RightZ => (GamepadAxis::PositiveRightZ, GamepadAxis::NegativeRightZ),
+            GamepadAxisType::Other(v) => (
+                GamepadAxis::PositiveOtherAxis(v),
+                GamepadAxis::NegativeOtherAxis(v),
+            ),
+        }
+    }
+}
+
 impl<T> InputMapper<T>
 where
     T: Copy + States,
@@ -47,11 +84,31 @@ where
         mut event: EventReader<GamepadButtonChangedEvent>,
     ) {
         let binding = im.gamepad_button_binding.clone();
+        let semantic_binding = im.semantic_binding.clone();
         let current_state = *state.get();
         for button_press in event.iter() {
             if let Some(action) = binding.get(&(current_state, button_press.button_type)) {
-                im.action_value
-                    .bind((current_state, (*action).clone()), button_press.value);
+                im.set_gamepad_input_value(
+                    current_state,
+                    action,
+                    InputBinding::GamepadButton(button_press.button_type),
+                    button_press.gamepad,
+                    button_press.value,
+                );
+            }
+            for ((st, semantic), action) in semantic_binding.iter() {
+                if st == &current_state
+                    && im.resolve_gamepad_semantic_button(button_press.gamepad, *semantic)
+                        == button_press.button_type
+                {
+                    im.set_gamepad_input_value(
+                        current_state,
+                        action,
+                        InputBinding::GamepadSemantic(*semantic),
+                        button_press.gamepad,
+                        button_press.value,
+                    );
+                }
             }
         }
     }
@@ -64,35 +121,49 @@ where
         let axis_binding = im.gamepad_axis_binding.clone();
         let current_state = *state.get();
         let set_val = |im: &mut ResMut<InputMapper<T>>,
+                       gamepad: Gamepad,
                        axis: (&GamepadAxis, &GamepadAxis),
                        val: (f32, f32)| {
-            if let Some(action) = axis_binding.get(&(current_state, axis.0.clone())) {
-                im.action_value
-                    .bind((current_state, (*action).clone()), val.0);
+            if let Some(action) = axis_binding.get(&(current_state, *axis.0)) {
+                im.set_gamepad_input_value(
+                    current_state,
+                    action,
+                    InputBinding::GamepadAxis(*axis.0),
+                    gamepad,
+                    val.0,
+                );
             }
-            if let Some(action) = axis_binding.get(&(current_state, axis.1.clone())) {
-                im.action_value
-                    .bind((current_state, (*action).clone()), val.1);
+            if let Some(action) = axis_binding.get(&(current_state, *axis.1)) {
+                im.set_gamepad_input_value(
+                    current_state,
+                    action,
+                    InputBinding::GamepadAxis(*axis.1),
+                    gamepad,
+                    val.1,
+                );
+            }
+        };
+        let s_bind = |im: &mut ResMut<InputMapper<T>>,
+                      gamepad: Gamepad,
+                      ref_val: f32,
+                      axis: (&GamepadAxis, &GamepadAxis)| {
+            match ref_val {
+                ö if ö > 0. => set_val(im, gamepad, axis, (ref_val, 0.)),
+                // idk if it makes any difference
+                // Option 1:
+                // ö if ö < 0. => set_val(im, gamepad, axis, (0., ref_val.abs())),
+                // Option 2:
+                // Which one is better, idk... I don't think it will make any difference anyway.
+                // If it does, pls. create an issue or make a PR.
+                ö if ö < 0. => set_val(im, gamepad, (axis.1, axis.0), (ref_val.abs(), 0.)),
+                _ => set_val(im, gamepad, axis, (0., 0.)),
             }
         };
-        let s_bind =
-            |im: &mut ResMut<InputMapper<T>>, ref_val: f32, axis: (&GamepadAxis, &GamepadAxis)| {
-                match ref_val {
-                    ö if ö > 0. => set_val(im, axis, (ref_val, 0.)),
-                    // idk if it makes any difference
-                    // Option 1:
-                    // ö if ö < 0. => set_val(im, axis, (0., ref_val.abs())),
-                    // Option 2:
-                    // Which one is better, idk... I don't think it will make any difference anyway.
-                    // If it does, pls. create an issue or make a PR.
-                    ö if ö < 0. => set_val(im, (axis.1, axis.0), (ref_val.abs(), 0.)),
-                    _ => set_val(im, axis, (0., 0.)),
-                }
-            };
         for motion in analog_motion.iter() {
             match motion.axis_type {
                 GamepadAxisType::LeftStickX => s_bind(
                     &mut im,
+                    motion.gamepad,
                     motion.value,
                     (
                         &GamepadAxis::PositiveLeftStickX,
@@ -101,6 +172,7 @@ where
                 ),
                 GamepadAxisType::LeftStickY => s_bind(
                     &mut im,
+                    motion.gamepad,
                     motion.value,
                     (
                         &GamepadAxis::PositiveLeftStickY,
@@ -109,11 +181,13 @@ where
                 ),
                 GamepadAxisType::LeftZ => s_bind(
                     &mut im,
+                    motion.gamepad,
                     motion.value,
                     (&GamepadAxis::PositiveLeftZ, &GamepadAxis::NegativeLeftZ),
                 ),
                 GamepadAxisType::RightStickX => s_bind(
                     &mut im,
+                    motion.gamepad,
                     motion.value,
                     (
                         &GamepadAxis::PositiveRightStickX,
@@ -122,6 +196,7 @@ where
                 ),
                 GamepadAxisType::RightStickY => s_bind(
                     &mut im,
+                    motion.gamepad,
                     motion.value,
                     (
                         &GamepadAxis::PositiveRightStickY,
@@ -130,11 +205,13 @@ where
                 ),
                 GamepadAxisType::RightZ => s_bind(
                     &mut im,
+                    motion.gamepad,
                     motion.value,
                     (&GamepadAxis::PositiveRightZ, &GamepadAxis::NegativeRightZ),
                 ),
                 GamepadAxisType::Other(v) => s_bind(
                     &mut im,
+                    motion.gamepad,
                     motion.value,
                     (
                         &GamepadAxis::PositiveOtherAxis(v),
@@ -144,4 +221,47 @@ where
             }
         }
     }
+
+    /// Releases the actions a disconnected gamepad was driving, so they finish on the next cycle
+    /// instead of staying at the last value it reported.
+    pub(crate) fn gamepad_disconnect_system(
+        mut im: ResMut<InputMapper<T>>,
+        mut connections: EventReader<GamepadConnectionEvent>,
+        mut device_lost: EventWriter<PlayerDeviceLost>,
+    ) {
+        for ev in connections.iter() {
+            if ev.connection != GamepadConnection::Disconnected {
+                continue;
+            }
+            let gamepad = Some(ev.gamepad);
+            let lost: Vec<(T, String)> = im
+                .input_values
+                .iter()
+                .filter(|(_, values)| {
+                    values
+                        .iter()
+                        .any(|(source, value)| source.gamepad == gamepad && *value > 0.)
+                })
+                .map(|(key, _)| key.to_owned())
+                .collect();
+            let keys: Vec<(T, String)> = im.input_values.keys().cloned().collect();
+            for key in keys.iter() {
+                im.remove_input_values(key, |source| source.gamepad == gamepad);
+            }
+            // Actions still held by another device, or another gamepad, keep their value.
+            let released: Vec<(T, String)> = lost
+                .iter()
+                .filter(|key| im.raw_action_value.get(*key).copied().unwrap_or(0.) == 0.)
+                .cloned()
+                .collect();
+            im.release_where(|key| released.contains(key));
+            let mut actions: Vec<String> = lost.into_iter().map(|(_, action)| action).collect();
+            actions.sort();
+            actions.dedup();
+            device_lost.send(PlayerDeviceLost {
+                gamepad: ev.gamepad,
+                actions,
+            });
+        }
+    }
 }
@@ -1,11 +1,18 @@
-use bevy::prelude::*;
+use bevy::{input::keyboard::KeyboardInput, prelude::*};
 
-use crate::{AutoBinder, InputMapper};
+use crate::{input::binding::InputBinding, AutoBinder, InputMapper};
 
 impl<T> InputMapper<T>
 where
     T: Copy + States,
 {
+    /// Resolves a physical key to the [`KeyCode`] the user's keyboard layout produces for it.
+    /// Useful for showing the right key label in prompts when binding by [`ScanCode`].
+    /// Returns `None` until the physical key has been pressed at least once.
+    pub fn scancode_label(&self, scan_code: ScanCode) -> Option<KeyCode> {
+        self.scancode_layout.get(&scan_code).copied()
+    }
+
     pub(crate) fn keyboard_key_press_system(
         mut im: ResMut<InputMapper<T>>,
         state: Res<State<T>>,
@@ -16,12 +23,43 @@ where
         for ((st, key), action) in im_iter.iter() {
             if st == &current_state {
                 if input.pressed(*key) {
-                    im.action_value.bind((current_state, action.to_owned()), 1.);
+                    im.set_input_value(current_state, action, InputBinding::Keyboard(*key), 1.);
                 }
                 if input.just_released(*key) {
-                    im.action_value.bind((current_state, action.to_owned()), 0.);
+                    im.set_input_value(current_state, action, InputBinding::Keyboard(*key), 0.);
                 }
             }
         }
     }
+
+    pub(crate) fn keyboard_scancode_press_system(
+        mut im: ResMut<InputMapper<T>>,
+        state: Res<State<T>>,
+        input: Res<Input<ScanCode>>,
+    ) {
+        let im_iter = im.scancode_binding.clone();
+        let current_state = *state.get();
+        for ((st, scan_code), action) in im_iter.iter() {
+            if st == &current_state {
+                let input_binding = InputBinding::ScanCode(*scan_code);
+                if input.pressed(*scan_code) {
+                    im.set_input_value(current_state, action, input_binding, 1.);
+                }
+                if input.just_released(*scan_code) {
+                    im.set_input_value(current_state, action, input_binding, 0.);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn keyboard_layout_system(
+        mut im: ResMut<InputMapper<T>>,
+        mut keyboard: EventReader<KeyboardInput>,
+    ) {
+        for ev in keyboard.iter() {
+            if let Some(key_code) = ev.key_code {
+                im.scancode_layout.bind(ScanCode(ev.scan_code), key_code);
+            }
+        }
+    }
 }
@@ -1,5 +1,22 @@
+pub mod accessibility;
+pub mod action;
+pub mod action_state;
+pub mod aggregate;
+pub mod binding;
+pub mod defaults;
+pub mod display;
 pub mod events;
+pub mod fixed;
+pub mod focus;
 pub mod gamepad;
 pub mod keyboard;
 pub mod mouse;
+pub mod navigation;
+pub mod pointer;
+pub mod rebind;
+pub mod replay;
+pub mod rumble;
+pub mod semantic;
+pub mod snapshot;
 pub mod state;
+pub mod suspend;
@@ -1,6 +1,10 @@
-use bevy::{input::mouse::MouseMotion, prelude::*};
+use bevy::{
+    input::mouse::MouseMotion,
+    prelude::*,
+    window::{CursorGrabMode, PrimaryWindow},
+};
 
-use crate::{AutoBinder, InputMapper};
+use crate::{input::binding::InputBinding, InputMapper};
 
 /// Represents a mouse's 2D movement axes.
 /// Horizontal axis is X, Vertical axis is Y.
@@ -11,7 +15,8 @@ use crate::{AutoBinder, InputMapper};
 /// - Moving mouse to left is NegativeX,
 /// - Moving mouse to down is PositiveY,
 /// - Moving mouse to up is NegativeY,
-#[derive(Default, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseAxis {
     /// Horizontal positive movement.
     #[default]
@@ -30,46 +35,118 @@ impl<T> InputMapper<T>
 where
     T: Copy + States,
 {
+    /// Grabs and hides the cursor with `mode` while the scenario has mouse axis bindings
+    /// and the primary window is focused. `None` gives back a grabbed cursor, then leaves
+    /// the cursor alone.
+    pub fn set_cursor_grab(&mut self, mode: Option<CursorGrabMode>) -> &mut Self {
+        self.cursor_grab = mode;
+        self
+    }
+
+    pub(crate) fn cursor_grab_system(
+        mut im: ResMut<InputMapper<T>>,
+        state: Res<State<T>>,
+        mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    ) {
+        let Ok(mut window) = windows.get_single_mut() else {
+            return;
+        };
+        let current_state = *state.get();
+        let mouse_look = im
+            .mouse_axis_binding
+            .keys()
+            .any(|(st, _)| st == &current_state);
+        let grab = im
+            .cursor_grab
+            .filter(|_| mouse_look && window.focused && !im.is_suspended());
+        // Only transitions touch the window, so the app may still change it while grabbed.
+        if grab == im.cursor_grabbed {
+            return;
+        }
+        im.cursor_grabbed = grab;
+        let (grab_mode, visible) = match grab {
+            Some(mode) => (mode, false),
+            None => (CursorGrabMode::None, true),
+        };
+        window.cursor.grab_mode = grab_mode;
+        window.cursor.visible = visible;
+    }
+
     pub(crate) fn mouse_axis_move_system(
         mut im: ResMut<InputMapper<T>>,
         state: Res<State<T>>,
         mut mouse_motion: EventReader<MouseMotion>,
+        windows: Query<&Window, With<PrimaryWindow>>,
     ) {
         let axis_binding = im.mouse_axis_binding.clone();
         let current_state = *state.get();
         let clear_x = |im: &mut ResMut<InputMapper<T>>| {
             if let Some(action) = axis_binding.get(&(current_state, MouseAxis::PositiveX)) {
-                im.action_value.bind((current_state, (*action).clone()), 0.);
+                im.set_input_value(
+                    current_state,
+                    action,
+                    InputBinding::MouseAxis(MouseAxis::PositiveX),
+                    0.,
+                );
             }
             if let Some(action) = axis_binding.get(&(current_state, MouseAxis::NegativeX)) {
-                im.action_value.bind((current_state, (*action).clone()), 0.);
+                im.set_input_value(
+                    current_state,
+                    action,
+                    InputBinding::MouseAxis(MouseAxis::NegativeX),
+                    0.,
+                );
             }
         };
         let clear_y = |im: &mut ResMut<InputMapper<T>>| {
             if let Some(action) = axis_binding.get(&(current_state, MouseAxis::PositiveY)) {
-                im.action_value.bind((current_state, (*action).clone()), 0.);
+                im.set_input_value(
+                    current_state,
+                    action,
+                    InputBinding::MouseAxis(MouseAxis::PositiveY),
+                    0.,
+                );
             }
             if let Some(action) = axis_binding.get(&(current_state, MouseAxis::NegativeY)) {
-                im.action_value.bind((current_state, (*action).clone()), 0.);
+                im.set_input_value(
+                    current_state,
+                    action,
+                    InputBinding::MouseAxis(MouseAxis::NegativeY),
+                    0.,
+                );
             }
         };
         let clear = |im: &mut ResMut<InputMapper<T>>| {
             clear_x(im);
             clear_y(im);
         };
+        // The cursor may be moving over another window.
+        if windows.get_single().is_ok_and(|window| !window.focused) {
+            mouse_motion.clear();
+            clear(&mut im);
+            return;
+        }
         if let Some(motion) = mouse_motion.iter().last() {
             // NOTE: Did `ö` got your attention? Be unusual when it comes to naming variables...
             match motion.delta.x {
                 ö if ö > 0. => {
                     if let Some(action) = axis_binding.get(&(current_state, MouseAxis::PositiveX)) {
-                        im.action_value
-                            .bind((current_state, (*action).clone()), motion.delta.x);
+                        im.set_input_value(
+                            current_state,
+                            action,
+                            InputBinding::MouseAxis(MouseAxis::PositiveX),
+                            motion.delta.x,
+                        );
                     }
                 }
                 ö if ö < 0. => {
                     if let Some(action) = axis_binding.get(&(current_state, MouseAxis::NegativeX)) {
-                        im.action_value
-                            .bind((current_state, (*action).clone()), motion.delta.x.abs());
+                        im.set_input_value(
+                            current_state,
+                            action,
+                            InputBinding::MouseAxis(MouseAxis::NegativeX),
+                            motion.delta.x.abs(),
+                        );
                     }
                 }
                 _ => clear_x(&mut im),
@@ -77,14 +154,22 @@ where
             match motion.delta.y {
                 ö if ö > 0. => {
                     if let Some(action) = axis_binding.get(&(current_state, MouseAxis::PositiveY)) {
-                        im.action_value
-                            .bind((current_state, (*action).clone()), motion.delta.y);
+                        im.set_input_value(
+                            current_state,
+                            action,
+                            InputBinding::MouseAxis(MouseAxis::PositiveY),
+                            motion.delta.y,
+                        );
                     }
                 }
                 ö if ö < 0. => {
                     if let Some(action) = axis_binding.get(&(current_state, MouseAxis::NegativeY)) {
-                        im.action_value
-                            .bind((current_state, (*action).clone()), motion.delta.y.abs());
+                        im.set_input_value(
+                            current_state,
+                            action,
+                            InputBinding::MouseAxis(MouseAxis::NegativeY),
+                            motion.delta.y.abs(),
+                        );
                     }
                 }
                 _ => clear_y(&mut im),
@@ -103,11 +188,12 @@ where
         let current_state = *state.get();
         for ((st, button), action) in im_iter.iter() {
             if st == &current_state {
+                let input_binding = InputBinding::MouseButton(*button);
                 if input.pressed(*button) {
-                    im.action_value.bind((current_state, action.to_owned()), 1.);
+                    im.set_input_value(current_state, action, input_binding, 1.);
                 }
                 if input.just_released(*button) {
-                    im.action_value.bind((current_state, action.to_owned()), 0.);
+                    im.set_input_value(current_state, action, input_binding, 0.);
                 }
             }
         }
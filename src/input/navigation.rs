@@ -0,0 +1,338 @@
+use bevy::prelude::*;
+
+use crate::{
+    input::{
+        accessibility::AutoRepeat,
+        binding::{InputBinding, InputSource},
+        gamepad::GamepadAxis,
+        semantic::SemanticButton,
+    },
+    AutoBinder, InputMapper,
+};
+
+/// Menu actions bound by [`InputMapper::bind_menu_navigation`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MenuAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Cancel,
+    NextTab,
+    PreviousTab,
+}
+
+impl MenuAction {
+    pub const ALL: [MenuAction; 8] = [
+        MenuAction::Up,
+        MenuAction::Down,
+        MenuAction::Left,
+        MenuAction::Right,
+        MenuAction::Confirm,
+        MenuAction::Cancel,
+        MenuAction::NextTab,
+        MenuAction::PreviousTab,
+    ];
+
+    /// Name of the action in the [`InputMapper`].
+    pub fn action_name(&self) -> &'static str {
+        match self {
+            MenuAction::Up => "menu_up",
+            MenuAction::Down => "menu_down",
+            MenuAction::Left => "menu_left",
+            MenuAction::Right => "menu_right",
+            MenuAction::Confirm => "menu_confirm",
+            MenuAction::Cancel => "menu_cancel",
+            MenuAction::NextTab => "menu_next_tab",
+            MenuAction::PreviousTab => "menu_previous_tab",
+        }
+    }
+    pub fn from_action_name(action: &str) -> Option<MenuAction> {
+        MenuAction::ALL
+            .into_iter()
+            .find(|menu_action| menu_action.action_name() == action)
+    }
+    /// Moves the focus of a list or a row of tabs with `len` items. Up, Left and PreviousTab
+    /// step back, Down, Right and NextTab forward. With `wrap`, stepping past an end continues
+    /// from the other one, otherwise the focus stays at the end.
+    pub fn step_focus(&self, index: usize, len: usize, wrap: bool) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        let index = index.min(len - 1);
+        match self {
+            MenuAction::Up | MenuAction::Left | MenuAction::PreviousTab => match index {
+                0 if wrap => len - 1,
+                0 => 0,
+                _ => index - 1,
+            },
+            MenuAction::Down | MenuAction::Right | MenuAction::NextTab => match index + 1 {
+                next if next < len => next,
+                _ if wrap => 0,
+                _ => index,
+            },
+            MenuAction::Confirm | MenuAction::Cancel => index,
+        }
+    }
+    fn is_direction(&self) -> bool {
+        matches!(
+            self,
+            MenuAction::Up | MenuAction::Down | MenuAction::Left | MenuAction::Right
+        )
+    }
+}
+
+/// Sent whenever a menu action is pressed or repeated.
+#[derive(Event)]
+pub struct MenuNavigation(pub MenuAction);
+
+/// Turns an analog action into a button, pressed above `press` and released below `release`.
+/// Keeping `release` under `press` stops a stick resting near the threshold from flickering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ButtonThreshold {
+    pub press: f32,
+    pub release: f32,
+}
+
+impl ButtonThreshold {
+    /// Turns the value into 1 while pressed and 0 otherwise, updating `pressed`.
+    pub(crate) fn apply(&self, pressed: &mut bool, value: f32) -> f32 {
+        *pressed = if *pressed {
+            value > self.release
+        } else {
+            value >= self.press
+        };
+        if *pressed {
+            1.
+        } else {
+            0.
+        }
+    }
+}
+
+/// Options for [`InputMapper::bind_menu_navigation`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MenuNavigationOptions {
+    /// Repeat timing of held directions.
+    pub repeat: AutoRepeat,
+    /// When a left stick direction counts as pressed.
+    pub stick: ButtonThreshold,
+    pub keyboard: bool,
+    pub gamepad: bool,
+}
+
+impl Default for MenuNavigationOptions {
+    fn default() -> Self {
+        MenuNavigationOptions {
+            repeat: AutoRepeat::default(),
+            stick: ButtonThreshold {
+                press: 0.6,
+                release: 0.4,
+            },
+            keyboard: true,
+            gamepad: true,
+        }
+    }
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Binds the [`MenuAction`]s in the scenario: arrow keys, Enter, Escape, Tab and Page Up,
+    /// the D-pad, left stick, semantic confirm and cancel, and the bumpers.
+    /// Menus usually get a scenario of their own, as these keys may be bound to gameplay.
+    /// [`MenuNavigation`] is only sent for menu actions started in such a scenario.
+    pub fn bind_menu_navigation(
+        &mut self,
+        scenario: T,
+        options: MenuNavigationOptions,
+    ) -> &mut Self {
+        self.menu_scenarios.insert(scenario);
+        if options.keyboard {
+            self.bind_keyboard_key_press(scenario, KeyCode::Up, MenuAction::Up.action_name())
+                .bind_keyboard_key_press(scenario, KeyCode::Down, MenuAction::Down.action_name())
+                .bind_keyboard_key_press(scenario, KeyCode::Left, MenuAction::Left.action_name())
+                .bind_keyboard_key_press(scenario, KeyCode::Right, MenuAction::Right.action_name())
+                .bind_keyboard_key_press(
+                    scenario,
+                    KeyCode::Return,
+                    MenuAction::Confirm.action_name(),
+                )
+                .bind_keyboard_key_press(
+                    scenario,
+                    KeyCode::NumpadEnter,
+                    MenuAction::Confirm.action_name(),
+                )
+                .bind_keyboard_key_press(
+                    scenario,
+                    KeyCode::Escape,
+                    MenuAction::Cancel.action_name(),
+                )
+                .bind_keyboard_key_press(scenario, KeyCode::Tab, MenuAction::NextTab.action_name())
+                .bind_keyboard_key_press(
+                    scenario,
+                    KeyCode::PageUp,
+                    MenuAction::PreviousTab.action_name(),
+                )
+                .bind_keyboard_key_press(
+                    scenario,
+                    KeyCode::PageDown,
+                    MenuAction::NextTab.action_name(),
+                );
+        }
+        if options.gamepad {
+            self.bind_gamepad_button_press(
+                scenario,
+                GamepadButtonType::DPadUp,
+                MenuAction::Up.action_name(),
+            )
+            .bind_gamepad_button_press(
+                scenario,
+                GamepadButtonType::DPadDown,
+                MenuAction::Down.action_name(),
+            )
+            .bind_gamepad_button_press(
+                scenario,
+                GamepadButtonType::DPadLeft,
+                MenuAction::Left.action_name(),
+            )
+            .bind_gamepad_button_press(
+                scenario,
+                GamepadButtonType::DPadRight,
+                MenuAction::Right.action_name(),
+            )
+            .bind_gamepad_axis_press(
+                scenario,
+                GamepadAxis::PositiveLeftStickY,
+                options.stick,
+                MenuAction::Up.action_name(),
+            )
+            .bind_gamepad_axis_press(
+                scenario,
+                GamepadAxis::NegativeLeftStickY,
+                options.stick,
+                MenuAction::Down.action_name(),
+            )
+            .bind_gamepad_axis_press(
+                scenario,
+                GamepadAxis::NegativeLeftStickX,
+                options.stick,
+                MenuAction::Left.action_name(),
+            )
+            .bind_gamepad_axis_press(
+                scenario,
+                GamepadAxis::PositiveLeftStickX,
+                options.stick,
+                MenuAction::Right.action_name(),
+            )
+            .bind_gamepad_semantic_press(
+                scenario,
+                SemanticButton::Confirm,
+                MenuAction::Confirm.action_name(),
+            )
+            .bind_gamepad_semantic_press(
+                scenario,
+                SemanticButton::Cancel,
+                MenuAction::Cancel.action_name(),
+            )
+            .bind_gamepad_button_press(
+                scenario,
+                GamepadButtonType::RightTrigger,
+                MenuAction::NextTab.action_name(),
+            )
+            .bind_gamepad_button_press(
+                scenario,
+                GamepadButtonType::LeftTrigger,
+                MenuAction::PreviousTab.action_name(),
+            );
+        }
+        for menu_action in MenuAction::ALL {
+            if menu_action.is_direction() {
+                self.set_auto_repeat(menu_action.action_name(), options.repeat);
+            }
+        }
+        self
+    }
+    /// Makes the action a button, in every scenario. See [`ButtonThreshold`].
+    /// The threshold applies to the action's combined value, so it also turns the action's
+    /// other inputs into buttons. Prefer [`InputMapper::set_input_threshold`] for a single input.
+    pub fn set_button_threshold(
+        &mut self,
+        action: impl ToString,
+        threshold: ButtonThreshold,
+    ) -> &mut Self {
+        self.button_thresholds.bind(action.to_string(), threshold);
+        self
+    }
+    pub fn clear_button_threshold(&mut self, action: &str) -> &mut Self {
+        self.button_thresholds.unbind(action.to_owned());
+        self
+    }
+    /// Makes a single input a button in the scenario, e.g. a trigger, while other inputs
+    /// bound to the same action keep their analog values. See [`ButtonThreshold`].
+    /// The threshold goes away when the input is unbound, and thresholds remembered by
+    /// [`InputMapper::set_defaults`] come back when the bindings are reset to the defaults.
+    pub fn set_input_threshold(
+        &mut self,
+        scenario: T,
+        input: InputBinding,
+        threshold: ButtonThreshold,
+    ) -> &mut Self {
+        self.input_thresholds.bind((scenario, input), threshold);
+        self
+    }
+    /// Removes the input's threshold, its value is passed through again.
+    pub fn clear_input_threshold(&mut self, scenario: T, input: InputBinding) -> &mut Self {
+        self.input_thresholds.unbind((scenario, input));
+        self.input_threshold_pressed
+            .retain(|(st, source)| st != &scenario || source.input != input);
+        self
+    }
+    pub fn input_threshold(&self, scenario: T, input: &InputBinding) -> Option<ButtonThreshold> {
+        self.input_thresholds.get(&(scenario, *input)).copied()
+    }
+
+    /// Applies the input's [`ButtonThreshold`] to its value, if it has one.
+    pub(crate) fn apply_input_threshold(
+        &mut self,
+        scenario: T,
+        source: InputSource,
+        value: f32,
+    ) -> f32 {
+        let Some(threshold) = self
+            .input_thresholds
+            .get(&(scenario, source.input))
+            .copied()
+        else {
+            return value;
+        };
+        let key = (scenario, source);
+        let mut pressed = self.input_threshold_pressed.contains(&key);
+        let value = threshold.apply(&mut pressed, value);
+        if pressed {
+            self.input_threshold_pressed.insert(key);
+        } else {
+            self.input_threshold_pressed.remove(&key);
+        }
+        value
+    }
+
+    pub(crate) fn menu_navigation_system(
+        im: Res<InputMapper<T>>,
+        mut navigation: EventWriter<MenuNavigation>,
+    ) {
+        for (scenario, action) in im.started_actions.iter() {
+            if !im.menu_scenarios.contains(scenario) {
+                continue;
+            }
+            if let Some(menu_action) = MenuAction::from_action_name(action) {
+                navigation.send(MenuNavigation(menu_action));
+            }
+        }
+    }
+}
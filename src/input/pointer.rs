@@ -0,0 +1,200 @@
+use bevy::{input::gamepad::GamepadAxis as BevyGamepadAxis, prelude::*, window::PrimaryWindow};
+
+use crate::{AutoBinder, InputMapper};
+
+/// Where a pointer action is measured, see [`InputMapper::bind_pointer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerSpace {
+    /// Logical pixels from the primary window's top left corner.
+    Window,
+    /// 0 to 1 from the primary window's top left corner.
+    Normalized,
+    /// 0 to 1 from the top left corner of the camera's viewport, so every split-screen camera
+    /// gets its own. Outside of 0 to 1 when the pointer is over another viewport.
+    Viewport { camera: Entity },
+    /// The point under the pointer on a plane, seen through the camera's viewport.
+    World {
+        camera: Entity,
+        plane_origin: Vec3,
+        plane_normal: Vec3,
+    },
+}
+
+impl PointerSpace {
+    /// The XY plane, for 2D cameras.
+    pub fn world_2d(camera: Entity) -> Self {
+        PointerSpace::World {
+            camera,
+            plane_origin: Vec3::ZERO,
+            plane_normal: Vec3::Z,
+        }
+    }
+    /// The ground plane with Y up, for top-down 3D cameras.
+    pub fn ground(camera: Entity) -> Self {
+        PointerSpace::World {
+            camera,
+            plane_origin: Vec3::ZERO,
+            plane_normal: Vec3::Y,
+        }
+    }
+}
+
+/// Value of a pointer action.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerValue {
+    /// Position in the action's [`PointerSpace`]. Window positions have a zero Z.
+    pub position: Vec3,
+    /// The ray from the camera through the pointer, for [`PointerSpace::World`].
+    pub ray: Option<Ray>,
+}
+
+/// A cursor moved by a gamepad stick, which takes over from the mouse cursor while
+/// the stick is tilted. The mouse takes over again once it moves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VirtualCursor {
+    pub x_axis: GamepadAxisType,
+    pub y_axis: GamepadAxisType,
+    /// Speed at full tilt, in logical pixels per second.
+    pub speed: f32,
+    /// Tilts below this are ignored.
+    pub deadzone: f32,
+}
+
+impl Default for VirtualCursor {
+    fn default() -> Self {
+        VirtualCursor {
+            x_axis: GamepadAxisType::RightStickX,
+            y_axis: GamepadAxisType::RightStickY,
+            speed: 800.,
+            deadzone: 0.15,
+        }
+    }
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Binds the pointer, the mouse cursor or the [`VirtualCursor`], to an action.
+    /// Read it with [`InputMapper::pointer_value`].
+    pub fn bind_pointer(
+        &mut self,
+        scenario: T,
+        space: PointerSpace,
+        action: impl ToString,
+    ) -> &mut Self {
+        self.pointer_binding
+            .bind((scenario, action.to_string()), space);
+        self
+    }
+    /// Lets a gamepad stick move the pointer.
+    pub fn set_virtual_cursor(&mut self, virtual_cursor: Option<VirtualCursor>) -> &mut Self {
+        self.virtual_cursor = virtual_cursor;
+        self
+    }
+    /// The pointer in logical pixels from the primary window's top left corner, if known.
+    pub fn pointer_position(&self) -> Option<Vec2> {
+        self.pointer_position
+    }
+    /// The pointer action's value, `None` while the pointer is outside the window
+    /// or doesn't hit the action's plane.
+    pub fn pointer_value(&self, scenario: T, action: &str) -> Option<PointerValue> {
+        self.pointer_value
+            .get(&(scenario, action.to_owned()))
+            .copied()
+    }
+
+    pub(crate) fn pointer_system(
+        mut im: ResMut<InputMapper<T>>,
+        state: Res<State<T>>,
+        windows: Query<&Window, With<PrimaryWindow>>,
+        cameras: Query<(&Camera, &GlobalTransform)>,
+        gamepads: Res<Gamepads>,
+        gamepad_axes: Res<Axis<BevyGamepadAxis>>,
+        time: Res<Time>,
+    ) {
+        let current_state = *state.get();
+        im.pointer_value.clear();
+        let Ok(window) = windows.get_single() else {
+            return;
+        };
+        let cursor = window.cursor_position();
+        if cursor != im.last_cursor {
+            im.last_cursor = cursor;
+            im.pointer_position = cursor;
+        }
+        if let Some(virtual_cursor) = im.virtual_cursor {
+            let tilt = gamepads
+                .iter()
+                .map(|gamepad| {
+                    let axis = |axis_type| {
+                        gamepad_axes
+                            .get(BevyGamepadAxis::new(gamepad, axis_type))
+                            .unwrap_or(0.)
+                    };
+                    Vec2::new(axis(virtual_cursor.x_axis), axis(virtual_cursor.y_axis))
+                })
+                .find(|tilt| tilt.length() > virtual_cursor.deadzone);
+            if let Some(tilt) = tilt {
+                let size = Vec2::new(window.width(), window.height());
+                let from = im.pointer_position.unwrap_or(size / 2.);
+                // Tilting up moves up the screen, whose Y grows downwards.
+                let step = Vec2::new(tilt.x, -tilt.y) * virtual_cursor.speed * time.delta_seconds();
+                im.pointer_position = Some((from + step).clamp(Vec2::ZERO, size));
+            }
+        }
+        let Some(position) = im.pointer_position else {
+            return;
+        };
+        for ((st, action), space) in im.pointer_binding.clone() {
+            if st != current_state {
+                continue;
+            }
+            let value = match space {
+                PointerSpace::Window => Some(PointerValue {
+                    position: position.extend(0.),
+                    ray: None,
+                }),
+                PointerSpace::Normalized => {
+                    let size = Vec2::new(window.width(), window.height());
+                    (size.cmpgt(Vec2::ZERO).all()).then(|| PointerValue {
+                        position: (position / size).extend(0.),
+                        ray: None,
+                    })
+                }
+                PointerSpace::Viewport { camera } => cameras
+                    .get(camera)
+                    .ok()
+                    .and_then(|(camera, _)| camera.logical_viewport_rect())
+                    // A minimized window has an empty viewport.
+                    .filter(|viewport| viewport.size().cmpgt(Vec2::ZERO).all())
+                    .map(|viewport| PointerValue {
+                        position: ((position - viewport.min) / viewport.size()).extend(0.),
+                        ray: None,
+                    }),
+                PointerSpace::World {
+                    camera,
+                    plane_origin,
+                    plane_normal,
+                } => cameras
+                    .get(camera)
+                    .ok()
+                    .and_then(|(camera, transform)| {
+                        let viewport = camera.logical_viewport_rect()?;
+                        camera.viewport_to_world(transform, position - viewport.min)
+                    })
+                    .and_then(|ray| {
+                        let distance = ray.intersect_plane(plane_origin, plane_normal)?;
+                        Some(PointerValue {
+                            position: ray.get_point(distance),
+                            ray: Some(ray),
+                        })
+                    }),
+            };
+            if let Some(value) = value {
+                im.pointer_value.bind((st, action), value);
+            }
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use bevy::{
+    ecs::system::SystemParam,
+    input::{
+        gamepad::{GamepadAxisChangedEvent, GamepadButtonChangedEvent},
+        keyboard::KeyboardInput,
+        mouse::{MouseButtonInput, MouseMotion},
+        ButtonState,
+    },
+    prelude::*,
+};
+
+use crate::{
+    input::{
+        binding::{InputBinding, InputDevice, InputSource},
+        events::{RebindCancelled, RebindCompleted},
+        gamepad::GamepadAxis,
+        mouse::MouseAxis,
+    },
+    InputMapper,
+};
+
+/// Options for [`InputMapper::start_rebind`].
+#[derive(Clone)]
+pub struct RebindOptions {
+    /// Keys that cancel listening instead of being bound.
+    pub cancel_keys: Vec<KeyCode>,
+    /// Inputs that are ignored while listening.
+    pub excluded: Vec<InputBinding>,
+    /// Devices that are listened to.
+    pub devices: Vec<InputDevice>,
+    /// Binds physical keys (`ScanCode`) instead of `KeyCode`s.
+    pub physical_keys: bool,
+    /// How far a gamepad button or axis must be pushed, from 0 to 1.
+    pub gamepad_threshold: f32,
+    /// How far the mouse must move in a single frame, in pixels, to bind a [`MouseAxis`].
+    /// `None` only listens to mouse buttons, e.g. for actions that shouldn't be bound to motion.
+    pub mouse_motion_threshold: Option<f32>,
+    /// Removes the action's other bindings on the captured input's device.
+    pub replace_existing: bool,
+}
+
+impl Default for RebindOptions {
+    fn default() -> Self {
+        RebindOptions {
+            cancel_keys: vec![KeyCode::Escape],
+            excluded: vec![],
+            devices: vec![
+                InputDevice::Keyboard,
+                InputDevice::Mouse,
+                InputDevice::Gamepad,
+            ],
+            physical_keys: false,
+            gamepad_threshold: 0.5,
+            mouse_motion_threshold: Some(20.),
+            replace_existing: true,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct PendingRebind<T> {
+    scenario: T,
+    action: String,
+    options: RebindOptions,
+}
+
+enum Capture {
+    Cancel(KeyCode),
+    Input(InputSource),
+}
+
+impl Capture {
+    fn input(input: InputBinding, gamepad: Option<Gamepad>) -> Self {
+        Capture::Input(InputSource { input, gamepad })
+    }
+}
+
+#[derive(SystemParam)]
+pub(crate) struct RawInputEvents<'w, 's> {
+    keyboard: EventReader<'w, 's, KeyboardInput>,
+    mouse_button: EventReader<'w, 's, MouseButtonInput>,
+    mouse_motion: EventReader<'w, 's, MouseMotion>,
+    gamepad_button: EventReader<'w, 's, GamepadButtonChangedEvent>,
+    gamepad_axis: EventReader<'w, 's, GamepadAxisChangedEvent>,
+}
+
+impl<'w, 's> RawInputEvents<'w, 's> {
+    fn clear(&mut self) {
+        self.keyboard.clear();
+        self.mouse_button.clear();
+        self.mouse_motion.clear();
+        self.gamepad_button.clear();
+        self.gamepad_axis.clear();
+    }
+
+    /// Reads every pending event and returns the first one that is accepted by the options.
+    fn capture(&mut self, options: &RebindOptions) -> Option<Capture> {
+        let accepts = |input: &InputBinding| {
+            options.devices.contains(&input.device()) && !options.excluded.contains(input)
+        };
+        let mut captured = None;
+        for ev in self.keyboard.iter() {
+            if ev.state != ButtonState::Pressed || captured.is_some() {
+                continue;
+            }
+            if let Some(key) = ev.key_code.filter(|k| options.cancel_keys.contains(k)) {
+                captured = Some(Capture::Cancel(key));
+                continue;
+            }
+            let input = if options.physical_keys {
+                Some(InputBinding::ScanCode(ScanCode(ev.scan_code)))
+            } else {
+                ev.key_code.map(InputBinding::Keyboard)
+            };
+            captured = input
+                .filter(accepts)
+                .map(|input| Capture::input(input, None));
+        }
+        for ev in self.mouse_button.iter() {
+            if ev.state == ButtonState::Pressed && captured.is_none() {
+                captured = Some(InputBinding::MouseButton(ev.button))
+                    .filter(accepts)
+                    .map(|input| Capture::input(input, None));
+            }
+        }
+        for ev in self.gamepad_button.iter() {
+            if ev.value >= options.gamepad_threshold && captured.is_none() {
+                captured = Some(InputBinding::GamepadButton(ev.button_type))
+                    .filter(accepts)
+                    .map(|input| Capture::input(input, Some(ev.gamepad)));
+            }
+        }
+        for ev in self.gamepad_axis.iter() {
+            if ev.value.abs() >= options.gamepad_threshold && captured.is_none() {
+                let (positive, negative) = GamepadAxis::halves(ev.axis_type);
+                let axis = if ev.value > 0. { positive } else { negative };
+                captured = Some(InputBinding::GamepadAxis(axis))
+                    .filter(accepts)
+                    .map(|input| Capture::input(input, Some(ev.gamepad)));
+            }
+        }
+        let delta: Vec2 = self.mouse_motion.iter().map(|m| m.delta).sum();
+        let moved = options
+            .mouse_motion_threshold
+            .is_some_and(|threshold| delta.abs().max_element() >= threshold);
+        if captured.is_none() && moved {
+            let axis = match delta {
+                d if d.x.abs() >= d.y.abs() && d.x > 0. => MouseAxis::PositiveX,
+                d if d.x.abs() >= d.y.abs() => MouseAxis::NegativeX,
+                d if d.y > 0. => MouseAxis::PositiveY,
+                _ => MouseAxis::NegativeY,
+            };
+            captured = Some(InputBinding::MouseAxis(axis))
+                .filter(accepts)
+                .map(|input| Capture::input(input, None));
+        }
+        captured
+    }
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Listens for the next input and binds it to the action.
+    /// Action mapping is suspended while listening, and the result is reported by
+    /// [`RebindCompleted`] or [`RebindCancelled`].
+    /// Only the devices the action's metadata allows are listened to. Returns `false`
+    /// without listening if the action is registered as not rebindable, or if none of
+    /// `options.devices` is left to listen to.
+    pub fn start_rebind(
+        &mut self,
+        scenario: T,
+        action: impl ToString,
+        mut options: RebindOptions,
+    ) -> bool {
+        let action = action.to_string();
+        if let Some(info) = self.action_info(&action) {
+            if !info.rebindable {
+                return false;
+            }
+            options
+                .devices
+                .retain(|device| info.devices.contains(device));
+        }
+        if options.devices.is_empty() {
+            return false;
+        }
+        self.release_all();
+        self.held_inputs.clear();
+        self.rebind = Some(PendingRebind {
+            scenario,
+            action,
+            options,
+        });
+        true
+    }
+    /// Stops listening without binding anything.
+    pub fn cancel_rebind(&mut self) -> &mut Self {
+        self.rebind = None;
+        self.held_inputs.clear();
+        self
+    }
+    /// The scenario and action that is waiting for an input, if any.
+    pub fn rebinding_action(&self) -> Option<(T, &str)> {
+        self.rebind
+            .as_ref()
+            .map(|pending| (pending.scenario, pending.action.as_str()))
+    }
+
+    pub(crate) fn rebind_system(
+        mut im: ResMut<InputMapper<T>>,
+        mut events: RawInputEvents,
+        mut completed: EventWriter<RebindCompleted<T>>,
+        mut cancelled: EventWriter<RebindCancelled<T>>,
+    ) {
+        let Some(pending) = im.rebind.clone() else {
+            events.clear();
+            return;
+        };
+        let PendingRebind {
+            scenario,
+            action,
+            options,
+        } = pending;
+        match events.capture(&options) {
+            Some(Capture::Cancel(key)) => {
+                im.cancel_rebind();
+                // A bound cancel key is still held, it shouldn't trigger its action.
+                let input = InputBinding::Keyboard(key);
+                if im.bound_action(scenario, &input).is_some() {
+                    im.held_inputs.insert(InputSource {
+                        input,
+                        gamepad: None,
+                    });
+                }
+                cancelled.send(RebindCancelled { scenario, action });
+            }
+            // Inputs of actions the user may not rebind are never taken away.
+            Some(Capture::Input(source))
+                if im
+                    .bound_action(scenario, &source.input)
+                    .is_some_and(|other| !im.can_rebind(other, &source.input)) => {}
+            Some(Capture::Input(source)) => {
+                let input = source.input;
+                let conflict = im
+                    .bound_action(scenario, &input)
+                    .filter(|bound| **bound != action)
+                    .cloned();
+                if conflict.is_some() {
+                    im.remove_binding(scenario, &input);
+                }
+                if options.replace_existing {
+                    for old in im.bound_inputs(scenario, &action) {
+                        if old.device() == input.device() {
+                            im.remove_binding(scenario, &old);
+                        }
+                    }
+                }
+                im.bind(scenario, input, &action);
+                im.cancel_rebind();
+                // The input is still held, it shouldn't trigger the action it was just bound to.
+                im.held_inputs.insert(source);
+                completed.send(RebindCompleted {
+                    scenario,
+                    action,
+                    input,
+                    conflict,
+                });
+            }
+            None => {}
+        }
+    }
+}
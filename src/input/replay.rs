@@ -0,0 +1,213 @@
+use std::{error::Error, fmt, fs, io, path::Path};
+
+use bevy::prelude::*;
+
+use crate::{AutoBinder, InputMapper};
+
+/// Action values of consecutive frames, recorded by [`InputMapper::start_recording`].
+/// Each frame holds the actions that weren't zero, as `(scenario, action, value)`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputRecording<T>
+where
+    T: Copy + States,
+{
+    pub frames: Vec<Vec<(T, String, f32)>>,
+}
+
+impl<T> Default for InputRecording<T>
+where
+    T: Copy + States,
+{
+    fn default() -> Self {
+        InputRecording { frames: vec![] }
+    }
+}
+
+/// A malformed line in the text format of [`InputRecording::to_text`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RecordingParseError {
+    /// The line number, starting at 1.
+    pub line: usize,
+}
+
+impl fmt::Display for RecordingParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "malformed input recording at line {}", self.line)
+    }
+}
+
+impl Error for RecordingParseError {}
+
+/// Escapes the characters the text format uses as separators.
+fn escape_field(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape_field(field: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        unescaped.push(match chars.next()? {
+            '\\' => '\\',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            _ => return None,
+        });
+    }
+    Some(unescaped)
+}
+
+impl<T> InputRecording<T>
+where
+    T: Copy + States,
+{
+    /// A line-based format, one `frame` line per frame followed by its
+    /// `scenario<TAB>action<TAB>value` lines. Scenarios are written with their `Debug` format.
+    /// Backslashes, tabs and line breaks in scenarios and actions are escaped with a backslash.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for frame in self.frames.iter() {
+            text.push_str("frame\n");
+            for (scenario, action, value) in frame.iter() {
+                let scenario = escape_field(&format!("{scenario:?}"));
+                let action = escape_field(action);
+                text.push_str(&format!("{scenario}\t{action}\t{value}\n"));
+            }
+        }
+        text
+    }
+    /// Reads the format of [`InputRecording::to_text`], `parse_scenario` turns the
+    /// `Debug` format of a scenario back into a scenario.
+    pub fn from_text(
+        text: &str,
+        parse_scenario: impl Fn(&str) -> Option<T>,
+    ) -> Result<Self, RecordingParseError> {
+        let mut recording = InputRecording::default();
+        for (index, line) in text.lines().enumerate() {
+            if line == "frame" {
+                recording.frames.push(vec![]);
+                continue;
+            }
+            let parse_line = || {
+                let mut fields = line.split('\t');
+                let scenario = parse_scenario(&unescape_field(fields.next()?)?)?;
+                let action = unescape_field(fields.next()?)?;
+                let value = fields.next()?.parse().ok()?;
+                fields.next().is_none().then_some((scenario, action, value))
+            };
+            let entry = parse_line().ok_or(RecordingParseError { line: index + 1 })?;
+            recording
+                .frames
+                .last_mut()
+                .ok_or(RecordingParseError { line: index + 1 })?
+                .push(entry);
+        }
+        Ok(recording)
+    }
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+    pub fn load(
+        path: impl AsRef<Path>,
+        parse_scenario: impl Fn(&str) -> Option<T>,
+    ) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::from_text(&text, parse_scenario)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Replay<T>
+where
+    T: Copy + States,
+{
+    recording: InputRecording<T>,
+    frame: usize,
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Records the action values every frame, until [`InputMapper::stop_recording`].
+    pub fn start_recording(&mut self) -> &mut Self {
+        self.recording = Some(InputRecording::default());
+        self
+    }
+    /// Stops recording and returns what was recorded.
+    pub fn stop_recording(&mut self) -> Option<InputRecording<T>> {
+        self.recording.take()
+    }
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+    /// Plays the recording back in place of live devices, one recorded frame per frame.
+    /// Action events are sent exactly as they were while recording.
+    pub fn start_replay(&mut self, recording: InputRecording<T>) -> &mut Self {
+        self.release_all();
+        self.replay = Some(Replay {
+            recording,
+            frame: 0,
+        });
+        self
+    }
+    /// Stops the replay and gives control back to live devices.
+    pub fn stop_replay(&mut self) -> &mut Self {
+        if self.replay.take().is_some() {
+            self.release_all();
+        }
+        self
+    }
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Overwrites the action values with the next recorded frame, if replaying.
+    pub(crate) fn replay_frame(&mut self) {
+        let Some(replay) = self.replay.as_mut() else {
+            return;
+        };
+        let Some(frame) = replay.recording.frames.get(replay.frame).cloned() else {
+            self.stop_replay();
+            return;
+        };
+        replay.frame += 1;
+        self.release_all();
+        for (scenario, action, value) in frame {
+            self.action_value.bind((scenario, action), value);
+        }
+    }
+    /// Appends the current action values to the recording, if recording.
+    pub(crate) fn record_frame(&mut self) {
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+        let mut frame = self
+            .action_value
+            .iter()
+            .filter(|(_, value)| **value != 0.)
+            .map(|((scenario, action), value)| (*scenario, action.to_owned(), *value))
+            .collect::<Vec<_>>();
+        frame.sort_by_cached_key(|(scenario, action, _)| {
+            (action.to_owned(), format!("{scenario:?}"))
+        });
+        recording.frames.push(frame);
+    }
+}
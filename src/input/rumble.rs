@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+};
+
+use crate::{AutoBinder, InputMapper};
+
+/// Rumble played on the gamepad that started an action.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RumblePattern {
+    pub intensity: GamepadRumbleIntensity,
+    pub duration: Duration,
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Rumbles the gamepad. Does nothing if the gamepad or platform does not support rumble.
+    pub fn rumble(
+        &mut self,
+        gamepad: Gamepad,
+        intensity: GamepadRumbleIntensity,
+        duration: Duration,
+    ) -> &mut Self {
+        self.rumble_requests.push(GamepadRumbleRequest::Add {
+            gamepad,
+            intensity,
+            duration,
+        });
+        self
+    }
+    /// Stops every rumble of the gamepad.
+    pub fn stop_rumble(&mut self, gamepad: Gamepad) -> &mut Self {
+        self.rumble_requests
+            .push(GamepadRumbleRequest::Stop { gamepad });
+        self
+    }
+    /// Rumbles the gamepad that last pressed the action.
+    /// Does nothing if the action was last pressed by another device.
+    pub fn rumble_action(
+        &mut self,
+        scenario: T,
+        action: &str,
+        intensity: GamepadRumbleIntensity,
+        duration: Duration,
+    ) -> &mut Self {
+        if let Some(gamepad) = self.action_gamepad(scenario, action) {
+            self.rumble(gamepad, intensity, duration);
+        }
+        self
+    }
+    /// Plays the pattern on the gamepad that starts the action, in any scenario.
+    pub fn set_action_rumble(
+        &mut self,
+        action: impl ToString,
+        pattern: RumblePattern,
+    ) -> &mut Self {
+        self.action_rumble.bind(action.to_string(), pattern);
+        self
+    }
+    /// The gamepad that last pressed the action, `None` if another device pressed it last.
+    pub fn action_gamepad(&self, scenario: T, action: &str) -> Option<Gamepad> {
+        self.last_active_input
+            .get(&(scenario, action.to_owned()))
+            .and_then(|source| source.gamepad)
+    }
+
+    /// Plays the patterns of the actions the last cycle started, on the gamepad that
+    /// pressed each action in the scenario it started in.
+    pub(crate) fn rumble_system(
+        mut im: ResMut<InputMapper<T>>,
+        mut rumble_requests: EventWriter<GamepadRumbleRequest>,
+    ) {
+        for (scenario, action) in im.started_actions.clone() {
+            if let Some(pattern) = im.action_rumble.get(&action).copied() {
+                im.rumble_action(scenario, &action, pattern.intensity, pattern.duration);
+            }
+        }
+        if !im.rumble_requests.is_empty() {
+            rumble_requests.send_batch(im.rumble_requests.drain(..));
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use bevy::{
+    input::gamepad::{GamepadConnection, GamepadConnectionEvent},
+    prelude::*,
+};
+
+use crate::{input::display::GamepadFamily, AutoBinder, InputMapper};
+
+/// Gamepad buttons bound by meaning instead of position.
+/// Nintendo controllers confirm with East and cancel with South, others the other way around.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SemanticButton {
+    Confirm,
+    Cancel,
+}
+
+impl SemanticButton {
+    /// The family's conventional button, without overrides.
+    pub fn default_button(&self, family: GamepadFamily) -> GamepadButtonType {
+        match (self, family) {
+            (SemanticButton::Confirm, GamepadFamily::Nintendo) => GamepadButtonType::East,
+            (SemanticButton::Cancel, GamepadFamily::Nintendo) => GamepadButtonType::South,
+            (SemanticButton::Confirm, _) => GamepadButtonType::South,
+            (SemanticButton::Cancel, _) => GamepadButtonType::East,
+        }
+    }
+}
+
+/// Names gamepads report, as listed in the SDL game controller database.
+const KNOWN_GAMEPAD_NAMES: &[(&str, GamepadFamily)] = &[
+    ("xbox 360 controller", GamepadFamily::Xbox),
+    ("xbox 360 wireless receiver", GamepadFamily::Xbox),
+    ("xbox one controller", GamepadFamily::Xbox),
+    ("xbox one elite controller", GamepadFamily::Xbox),
+    ("xbox one elite 2 controller", GamepadFamily::Xbox),
+    ("xbox one s controller", GamepadFamily::Xbox),
+    ("xbox series x controller", GamepadFamily::Xbox),
+    ("xbox wireless controller", GamepadFamily::Xbox),
+    ("xinput controller", GamepadFamily::Xbox),
+    ("ps3 controller", GamepadFamily::PlayStation),
+    ("ps4 controller", GamepadFamily::PlayStation),
+    ("ps5 controller", GamepadFamily::PlayStation),
+    ("dualsense wireless controller", GamepadFamily::PlayStation),
+    (
+        "dualsense edge wireless controller",
+        GamepadFamily::PlayStation,
+    ),
+    ("wireless controller", GamepadFamily::PlayStation),
+    ("nintendo switch pro controller", GamepadFamily::Nintendo),
+    ("pro controller", GamepadFamily::Nintendo),
+    ("joy-con (l)", GamepadFamily::Nintendo),
+    ("joy-con (r)", GamepadFamily::Nintendo),
+    ("joy-con (l/r)", GamepadFamily::Nintendo),
+    ("nintendo switch joy-con (l)", GamepadFamily::Nintendo),
+    ("nintendo switch joy-con (r)", GamepadFamily::Nintendo),
+    ("nintendo switch joy-con (l/r)", GamepadFamily::Nintendo),
+];
+
+impl GamepadFamily {
+    /// The family of a gamepad reporting a known name, e.g. "Xbox Wireless Controller".
+    /// Unknown names, including third-party pads that mimic a family, are [`GamepadFamily::Generic`].
+    pub fn from_name(name: &str) -> GamepadFamily {
+        let name = name.trim().to_lowercase();
+        KNOWN_GAMEPAD_NAMES
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|(_, family)| *family)
+            .unwrap_or(GamepadFamily::Generic)
+    }
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Binds a semantic button, which follows the current [`GamepadFamily`].
+    pub fn bind_gamepad_semantic_press(
+        &mut self,
+        scenario: T,
+        button: SemanticButton,
+        action: impl ToString,
+    ) -> &mut Self {
+        self.semantic_binding
+            .bind((scenario, button), action.to_string());
+        self
+    }
+    /// Uses another button for a semantic button on the family, e.g. to let
+    /// Nintendo players confirm with South.
+    pub fn override_semantic_button(
+        &mut self,
+        family: GamepadFamily,
+        semantic: SemanticButton,
+        button: GamepadButtonType,
+    ) -> &mut Self {
+        self.semantic_overrides.bind((family, semantic), button);
+        self
+    }
+    /// The gamepad button a semantic button currently stands for.
+    pub fn resolve_semantic_button(&self, semantic: SemanticButton) -> GamepadButtonType {
+        self.family_semantic_button(self.gamepad_family, semantic)
+    }
+    /// The button a semantic button stands for on the gamepad, following its own family.
+    pub fn resolve_gamepad_semantic_button(
+        &self,
+        gamepad: Gamepad,
+        semantic: SemanticButton,
+    ) -> GamepadButtonType {
+        self.family_semantic_button(self.gamepad_family_of(gamepad), semantic)
+    }
+    /// The family detected for the gamepad, or the current [`GamepadFamily`] if it
+    /// wasn't detected.
+    pub fn gamepad_family_of(&self, gamepad: Gamepad) -> GamepadFamily {
+        self.detected_gamepad_families
+            .get(&gamepad)
+            .copied()
+            .unwrap_or(self.gamepad_family)
+    }
+    fn family_semantic_button(
+        &self,
+        family: GamepadFamily,
+        semantic: SemanticButton,
+    ) -> GamepadButtonType {
+        self.semantic_overrides
+            .get(&(family, semantic))
+            .copied()
+            .unwrap_or_else(|| semantic.default_button(family))
+    }
+    /// When enabled, every gamepad's family is detected from its name, and the current
+    /// [`GamepadFamily`] follows the most recently connected gamepad.
+    pub fn set_gamepad_family_detection(&mut self, enabled: bool) -> &mut Self {
+        self.detect_gamepad_family = enabled;
+        self
+    }
+
+    pub(crate) fn gamepad_family_system(
+        mut im: ResMut<InputMapper<T>>,
+        mut connections: EventReader<GamepadConnectionEvent>,
+    ) {
+        for ev in connections.iter() {
+            match &ev.connection {
+                GamepadConnection::Connected(info) if im.detect_gamepad_family => {
+                    let family = GamepadFamily::from_name(&info.name);
+                    im.detected_gamepad_families.insert(ev.gamepad, family);
+                    im.gamepad_family = family;
+                }
+                GamepadConnection::Connected(_) => {}
+                GamepadConnection::Disconnected => {
+                    im.detected_gamepad_families.remove(&ev.gamepad);
+                }
+            }
+        }
+    }
+}
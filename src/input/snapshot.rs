@@ -0,0 +1,212 @@
+use bevy::prelude::*;
+
+use crate::{input::action::ActionKind, InputMapper};
+
+/// Axis values are sent as fixed point numbers with this many steps per unit.
+/// Values beyond `i16::MAX / AXIS_SCALE` are clamped.
+pub const AXIS_SCALE: f32 = 256.;
+
+/// Which action every bit and axis of an [`ActionSnapshot`] stands for.
+/// Both ends of a connection must use the same layout.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotLayout {
+    pub buttons: Vec<String>,
+    pub axes: Vec<String>,
+}
+
+impl SnapshotLayout {
+    fn button_bytes(&self) -> usize {
+        self.buttons.len().div_ceil(8)
+    }
+}
+
+/// Compact state of every action in a [`SnapshotLayout`]: one bit per button,
+/// one quantized `i16` per axis.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionSnapshot {
+    pub buttons: Vec<u8>,
+    pub axes: Vec<i16>,
+}
+
+/// Changes between two [`ActionSnapshot`]s of the same layout.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotDelta {
+    /// Bits of the buttons that flipped.
+    pub toggled_buttons: Vec<u8>,
+    /// Index and new value of the axes that changed.
+    pub axes: Vec<(u16, i16)>,
+}
+
+fn quantize(value: f32) -> i16 {
+    (value * AXIS_SCALE)
+        .round()
+        .clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
+
+fn dequantize(value: i16) -> f32 {
+    value as f32 / AXIS_SCALE
+}
+
+impl ActionSnapshot {
+    pub fn is_pressed(&self, index: usize) -> bool {
+        self.buttons
+            .get(index / 8)
+            .is_some_and(|byte| byte & (1 << (index % 8)) != 0)
+    }
+    pub fn axis(&self, index: usize) -> f32 {
+        self.axes.get(index).copied().map(dequantize).unwrap_or(0.)
+    }
+    /// Button bits followed by little endian axes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.buttons.clone();
+        for axis in self.axes.iter() {
+            bytes.extend_from_slice(&axis.to_le_bytes());
+        }
+        bytes
+    }
+    /// Reads [`ActionSnapshot::to_bytes`], `None` if the length doesn't match the layout.
+    pub fn from_bytes(bytes: &[u8], layout: &SnapshotLayout) -> Option<Self> {
+        let button_bytes = layout.button_bytes();
+        if bytes.len() != button_bytes + layout.axes.len() * 2 {
+            return None;
+        }
+        Some(ActionSnapshot {
+            buttons: bytes[..button_bytes].to_vec(),
+            axes: bytes[button_bytes..]
+                .chunks_exact(2)
+                .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+                .collect(),
+        })
+    }
+    /// What changed since `previous`.
+    pub fn diff(&self, previous: &ActionSnapshot) -> SnapshotDelta {
+        SnapshotDelta {
+            toggled_buttons: self
+                .buttons
+                .iter()
+                .zip(previous.buttons.iter())
+                .map(|(current, previous)| current ^ previous)
+                .collect(),
+            axes: self
+                .axes
+                .iter()
+                .zip(previous.axes.iter())
+                .enumerate()
+                .filter(|(_, (current, previous))| current != previous)
+                .map(|(index, (current, _))| (index as u16, *current))
+                .collect(),
+        }
+    }
+    /// Rebuilds the snapshot [`ActionSnapshot::diff`] was called on.
+    pub fn apply_delta(&self, delta: &SnapshotDelta) -> ActionSnapshot {
+        let mut snapshot = self.clone();
+        for (byte, toggled) in snapshot
+            .buttons
+            .iter_mut()
+            .zip(delta.toggled_buttons.iter())
+        {
+            *byte ^= toggled;
+        }
+        for (index, value) in delta.axes.iter() {
+            if let Some(axis) = snapshot.axes.get_mut(*index as usize) {
+                *axis = *value;
+            }
+        }
+        snapshot
+    }
+}
+
+impl SnapshotDelta {
+    pub fn is_empty(&self) -> bool {
+        self.axes.is_empty() && self.toggled_buttons.iter().all(|byte| *byte == 0)
+    }
+    /// Toggled bits, the number of changed axes, then index and value of each, little endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.toggled_buttons.clone();
+        bytes.extend_from_slice(&(self.axes.len() as u16).to_le_bytes());
+        for (index, value) in self.axes.iter() {
+            bytes.extend_from_slice(&index.to_le_bytes());
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+    /// Reads [`SnapshotDelta::to_bytes`], `None` if malformed.
+    pub fn from_bytes(bytes: &[u8], layout: &SnapshotLayout) -> Option<Self> {
+        let button_bytes = layout.button_bytes();
+        let count = u16::from_le_bytes(bytes.get(button_bytes..button_bytes + 2)?.try_into().ok()?);
+        let axes = bytes.get(button_bytes + 2..)?;
+        if axes.len() != count as usize * 4 {
+            return None;
+        }
+        Some(SnapshotDelta {
+            toggled_buttons: bytes[..button_bytes].to_vec(),
+            axes: axes
+                .chunks_exact(4)
+                .map(|chunk| {
+                    (
+                        u16::from_le_bytes([chunk[0], chunk[1]]),
+                        i16::from_le_bytes([chunk[2], chunk[3]]),
+                    )
+                })
+                .collect(),
+        })
+    }
+}
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// A layout of every registered action, sorted by name. Buttons are actions of
+    /// [`ActionKind::Button`], every other kind is an axis.
+    pub fn snapshot_layout(&self) -> SnapshotLayout {
+        let mut layout = SnapshotLayout::default();
+        for (action, info) in self.action_info.iter() {
+            match info.kind {
+                ActionKind::Button => layout.buttons.push(action.to_owned()),
+                ActionKind::Axis | ActionKind::DualAxis => layout.axes.push(action.to_owned()),
+            }
+        }
+        layout.buttons.sort();
+        layout.axes.sort();
+        layout
+    }
+    /// The scenario's current action values in the layout.
+    pub fn snapshot(&self, scenario: T, layout: &SnapshotLayout) -> ActionSnapshot {
+        let mut snapshot = ActionSnapshot {
+            buttons: vec![0; layout.button_bytes()],
+            axes: vec![],
+        };
+        for (index, action) in layout.buttons.iter().enumerate() {
+            if self.value(scenario, action) > 0. {
+                snapshot.buttons[index / 8] |= 1 << (index % 8);
+            }
+        }
+        for action in layout.axes.iter() {
+            snapshot.axes.push(quantize(self.value(scenario, action)));
+        }
+        snapshot
+    }
+    /// Sets the scenario's action values from the snapshot. Action events follow on the next cycle.
+    /// The values were already resolved by the sender, so the local press modes, thresholds and
+    /// auto repeat are not applied again, until a local device or [`InputMapper::set_value`]
+    /// drives the action.
+    pub fn apply_snapshot(
+        &mut self,
+        scenario: T,
+        layout: &SnapshotLayout,
+        snapshot: &ActionSnapshot,
+    ) -> &mut Self {
+        for (index, action) in layout.buttons.iter().enumerate() {
+            let value = if snapshot.is_pressed(index) { 1. } else { 0. };
+            self.set_resolved_value((scenario, action.to_owned()), value);
+        }
+        for (index, action) in layout.axes.iter().enumerate() {
+            self.set_resolved_value((scenario, action.to_owned()), snapshot.axis(index));
+        }
+        self
+    }
+}
@@ -1,11 +1,97 @@
 use bevy::prelude::*;
 
-use crate::InputMapper;
+use crate::{
+    input::binding::{InputBinding, InputSource},
+    AutoBinder, InputMapper,
+};
 
 impl<T> InputMapper<T>
 where
     T: Copy + States,
 {
+    /// The action's current value in the scenario, zero if it isn't active.
+    pub fn value(&self, state: T, action: &str) -> f32 {
+        *self.get_current_value(state, action)
+    }
+    /// Sets the action's value as if a device produced it, e.g. for AI agents or tutorials.
+    /// Action events follow on the next cycle. The value stays until it is changed again,
+    /// by this or by a device bound to the action.
+    pub fn set_value(&mut self, state: T, action: impl ToString, value: f32) -> &mut Self {
+        let key = (state, action.to_string());
+        self.resolved_values.remove(&key);
+        self.raw_action_value.bind(key, value);
+        self
+    }
+    /// Presses the action, see [`InputMapper::set_value`].
+    pub fn press(&mut self, state: T, action: impl ToString) -> &mut Self {
+        self.set_value(state, action, 1.)
+    }
+    /// Releases the action, see [`InputMapper::set_value`].
+    /// It finishes on the next cycle if it was held, or toggled on.
+    pub fn release(&mut self, state: T, action: impl ToString) -> &mut Self {
+        let key = (state, action.to_string());
+        self.resolve_state.remove(&key);
+        self.resolved_values.remove(&key);
+        if let Some(values) = self.input_values.remove(&key) {
+            self.input_threshold_pressed
+                .retain(|(st, source)| st != &state || !values.contains_key(source));
+        }
+        self.last_active_input.remove(&key);
+        if let Some(value) = self.action_value.get_mut(&key) {
+            *value = 0.;
+        }
+        self.raw_action_value.bind(key, 0.);
+        self
+    }
+
+    /// Records a keyboard or mouse input's value, see [`InputMapper::set_source_value`].
+    pub(crate) fn set_input_value(
+        &mut self,
+        state: T,
+        action: &str,
+        input: InputBinding,
+        value: f32,
+    ) {
+        let source = InputSource {
+            input,
+            gamepad: None,
+        };
+        self.set_source_value(state, action, source, value);
+    }
+    /// Records a gamepad input's value, kept apart from the same input on other gamepads.
+    pub(crate) fn set_gamepad_input_value(
+        &mut self,
+        state: T,
+        action: &str,
+        input: InputBinding,
+        gamepad: Gamepad,
+        value: f32,
+    ) {
+        let source = InputSource {
+            input,
+            gamepad: Some(gamepad),
+        };
+        self.set_source_value(state, action, source, value);
+    }
+    /// Records one input's value and writes the aggregated value as the action's raw value.
+    /// Inputs in [`InputMapper::held_inputs`] are ignored until they are released.
+    fn set_source_value(&mut self, state: T, action: &str, source: InputSource, value: f32) {
+        if self.held_inputs.contains(&source) {
+            if value > 0. {
+                return;
+            }
+            self.held_inputs.remove(&source);
+        }
+        let value = self.apply_input_threshold(state, source, value);
+        let key = (state, action.to_owned());
+        self.resolved_values.remove(&key);
+        let values = self.input_values.entry(key.clone()).or_default();
+        let previous = values.insert(source, value).unwrap_or(0.);
+        if value > 0. && previous <= 0. {
+            self.last_active_input.bind(key.clone(), source);
+        }
+        self.aggregate_input_values(key);
+    }
     pub(crate) fn get_previous_value(&self, state: T, action: &str) -> &f32 {
         self.previous_action_value
             .get(&(state, action.to_owned()))
@@ -30,4 +116,33 @@ where
             || self.is_continuing(state, action)
             || self.is_finished(state, action)
     }
+    /// Sets every action's value to zero, so held actions finish on the next cycle.
+    pub(crate) fn release_all(&mut self) {
+        self.release_where(|_| true);
+    }
+    /// Sets the value of every action matching `filter` to zero.
+    pub(crate) fn release_where(&mut self, filter: impl Fn(&(T, String)) -> bool) {
+        for (key, value) in self.raw_action_value.iter_mut() {
+            if filter(key) {
+                *value = 0.;
+            }
+        }
+        for (key, value) in self.action_value.iter_mut() {
+            if filter(key) {
+                *value = 0.;
+            }
+        }
+        let released_inputs: Vec<(T, InputSource)> = self
+            .input_values
+            .iter()
+            .filter(|(key, _)| filter(key))
+            .flat_map(|((st, _), values)| values.keys().map(move |source| (*st, *source)))
+            .collect();
+        self.input_threshold_pressed
+            .retain(|key| !released_inputs.contains(key));
+        self.input_values.retain(|key, _| !filter(key));
+        self.last_active_input.retain(|key, _| !filter(key));
+        self.resolve_state.retain(|key, _| !filter(key));
+        self.resolved_values.retain(|key| !filter(key));
+    }
 }
@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+#[cfg(feature = "egui")]
+use bevy_egui::EguiContext;
+
+use crate::InputMapper;
+
+/// Marker component for the UI entity that currently has keyboard focus, e.g. a chat box.
+/// While any entity has it and auto text capture is enabled, action mapping is suspended.
+#[derive(Component, Default)]
+pub struct TextCaptureFocus;
+
+impl<T> InputMapper<T>
+where
+    T: Copy + States,
+{
+    /// Pauses all action mapping. Held actions are released, so `InputActionFinished`
+    /// is sent for them on the next cycle.
+    pub fn suspend(&mut self) -> &mut Self {
+        if !self.is_suspended() {
+            self.release_all();
+        }
+        self.suspended = true;
+        self
+    }
+    /// Resumes action mapping paused by [`InputMapper::suspend`].
+    pub fn resume(&mut self) -> &mut Self {
+        self.suspended = false;
+        self
+    }
+    /// Whether action mapping is paused, either manually, by text capture or while rebinding.
+    pub fn is_suspended(&self) -> bool {
+        self.suspended || self.text_capture || self.rebind.is_some()
+    }
+    /// When enabled, action mapping is suspended while a [`TextCaptureFocus`] entity exists,
+    /// or, with the `egui` feature, while egui wants keyboard input.
+    pub fn set_auto_text_capture(&mut self, enabled: bool) -> &mut Self {
+        self.auto_text_capture = enabled;
+        if !enabled {
+            self.text_capture = false;
+        }
+        self
+    }
+
+    /// Live devices are ignored while suspended or replaying.
+    pub(crate) fn is_mapping(im: Res<InputMapper<T>>) -> bool {
+        !im.is_suspended() && !im.is_replaying()
+    }
+
+    pub(crate) fn text_capture_system(
+        mut im: ResMut<InputMapper<T>>,
+        focused: Query<(), With<TextCaptureFocus>>,
+        #[cfg(feature = "egui")] mut egui: Query<&mut EguiContext>,
+    ) {
+        if !im.auto_text_capture {
+            return;
+        }
+        #[allow(unused_mut)]
+        let mut wants_keyboard = !focused.is_empty();
+        #[cfg(feature = "egui")]
+        for mut ctx in egui.iter_mut() {
+            wants_keyboard |= ctx.get_mut().wants_keyboard_input();
+        }
+        if wants_keyboard && !im.is_suspended() {
+            im.release_all();
+        }
+        if im.text_capture != wants_keyboard {
+            im.text_capture = wants_keyboard;
+        }
+    }
+}
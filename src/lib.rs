@@ -1,12 +1,35 @@
 pub mod input;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 use std::{hash::Hash, marker::PhantomData};
 
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{
+    input::{gamepad::GamepadRumbleRequest, InputSystem},
+    prelude::*,
+    utils::{HashMap, HashSet},
+    window::{CursorGrabMode, WindowFocused},
+};
 use input::{
-    events::{InputActionContinuing, InputActionFinished, InputActionStarted},
+    accessibility::{AutoRepeat, PressMode, ResolveState},
+    action::ActionInfo,
+    action_state::ActionState,
+    aggregate::Aggregation,
+    binding::{InputBinding, InputSource},
+    display::GamepadFamily,
+    events::{
+        ActionEventSystem, DeviceInputSystem, InputActionContinuing, InputActionFinished,
+        InputActionStarted, PlayerDeviceLost, RebindCancelled, RebindCompleted,
+    },
+    fixed::{FixedActionState, FixedInputSystem, FixedLatch},
     gamepad::GamepadAxis,
     mouse::MouseAxis,
+    navigation::{ButtonThreshold, MenuNavigation},
+    pointer::{PointerSpace, PointerValue, VirtualCursor},
+    rebind::PendingRebind,
+    replay::{InputRecording, Replay},
+    rumble::RumblePattern,
+    semantic::SemanticButton,
 };
 
 use crate::input::events::InputActionActive;
@@ -39,15 +62,66 @@ pub struct InputMapper<T>
 where
     T: Copy + States,
 {
+    /// Values as written by devices, before [`PressMode`]s.
+    pub(crate) raw_action_value: HashMap<(T, String), f32>,
+    /// Values of each input bound to an action, see [`Aggregation`].
+    pub(crate) input_values: HashMap<(T, String), HashMap<InputSource, f32>>,
+    pub(crate) last_active_input: HashMap<(T, String), InputSource>,
+    pub(crate) aggregation: HashMap<String, Aggregation>,
     pub(crate) action_value: HashMap<(T, String), f32>,
     pub(crate) previous_action_value: HashMap<(T, String), f32>,
+    /// Actions started by the last cycle, with the scenario they started in.
+    pub(crate) started_actions: Vec<(T, String)>,
+    pub(crate) fixed_latch: HashMap<(T, String), FixedLatch>,
+    pub(crate) fixed_state: HashMap<(T, String), FixedActionState>,
 
     pub(crate) keyboard_binding: HashMap<(T, KeyCode), String>,
+    pub(crate) scancode_binding: HashMap<(T, ScanCode), String>,
+    pub(crate) scancode_layout: HashMap<ScanCode, KeyCode>,
     pub(crate) mouse_button_binding: HashMap<(T, MouseButton), String>,
     pub(crate) mouse_axis_binding: HashMap<(T, MouseAxis), String>,
+    pub(crate) pointer_binding: HashMap<(T, String), PointerSpace>,
+    pub(crate) pointer_value: HashMap<(T, String), PointerValue>,
+    pub(crate) pointer_position: Option<Vec2>,
+    pub(crate) last_cursor: Option<Vec2>,
+    pub(crate) virtual_cursor: Option<VirtualCursor>,
+    pub(crate) cursor_grab: Option<CursorGrabMode>,
+    /// How the mapper grabbed the cursor, to give it back once it shouldn't be grabbed.
+    pub(crate) cursor_grabbed: Option<CursorGrabMode>,
 
     pub(crate) gamepad_axis_binding: HashMap<(T, GamepadAxis), String>,
     pub(crate) gamepad_button_binding: HashMap<(T, GamepadButtonType), String>,
+    pub(crate) semantic_binding: HashMap<(T, SemanticButton), String>,
+    pub(crate) semantic_overrides: HashMap<(GamepadFamily, SemanticButton), GamepadButtonType>,
+    pub(crate) default_bindings: Vec<(T, InputBinding, String)>,
+    pub(crate) default_input_thresholds: HashMap<(T, InputBinding), ButtonThreshold>,
+    pub(crate) action_info: HashMap<String, ActionInfo>,
+    pub(crate) press_modes: HashMap<String, PressMode>,
+    pub(crate) auto_repeat: HashMap<String, AutoRepeat>,
+    pub(crate) button_thresholds: HashMap<String, ButtonThreshold>,
+    pub(crate) resolve_state: HashMap<(T, String), ResolveState>,
+    /// Actions whose raw value was resolved by someone else, e.g. a snapshot's sender.
+    pub(crate) resolved_values: HashSet<(T, String)>,
+    pub(crate) input_thresholds: HashMap<(T, InputBinding), ButtonThreshold>,
+    pub(crate) input_threshold_pressed: HashSet<(T, InputSource)>,
+    pub(crate) gamepad_family: GamepadFamily,
+    pub(crate) detect_gamepad_family: bool,
+    pub(crate) detected_gamepad_families: HashMap<Gamepad, GamepadFamily>,
+    pub(crate) menu_scenarios: HashSet<T>,
+
+    pub(crate) action_rumble: HashMap<String, RumblePattern>,
+    pub(crate) rumble_requests: Vec<GamepadRumbleRequest>,
+
+    pub(crate) recording: Option<InputRecording<T>>,
+    pub(crate) replay: Option<Replay<T>>,
+
+    pub(crate) suspended: bool,
+    pub(crate) text_capture: bool,
+    pub(crate) auto_text_capture: bool,
+    pub(crate) keep_on_focus_loss: HashSet<String>,
+    pub(crate) rebind: Option<PendingRebind<T>>,
+    /// Inputs ignored until they are released, e.g. the input a rebind just captured.
+    pub(crate) held_inputs: HashSet<InputSource>,
 }
 
 impl<T> InputMapper<T>
@@ -64,6 +138,18 @@ where
             .bind((scenario, key), action.to_string());
         self
     }
+    /// Binds a physical key, regardless of the user's keyboard layout.
+    /// `ScanCode`s are platform specific, use [`InputMapper::scancode_label`] to display them.
+    pub fn bind_keyboard_scancode_press(
+        &mut self,
+        scenario: T,
+        scan_code: ScanCode,
+        action: impl ToString,
+    ) -> &mut Self {
+        self.scancode_binding
+            .bind((scenario, scan_code), action.to_string());
+        self
+    }
     pub fn bind_mouse_axis_move(
         &mut self,
         scenario: T,
@@ -104,101 +190,81 @@ where
             .bind((scenario, button), action.to_string());
         self
     }
+    /// Binds an analog trigger as a button, so a lightly-touched trigger doesn't press the action.
+    pub fn bind_gamepad_trigger_press(
+        &mut self,
+        scenario: T,
+        button: GamepadButtonType,
+        threshold: ButtonThreshold,
+        action: impl ToString,
+    ) -> &mut Self {
+        self.bind_gamepad_button_press(scenario, button, action)
+            .set_input_threshold(scenario, InputBinding::GamepadButton(button), threshold)
+    }
+    /// Binds a stick direction as a button, pressed once the stick is pushed past the threshold.
+    pub fn bind_gamepad_axis_press(
+        &mut self,
+        scenario: T,
+        axis: GamepadAxis,
+        threshold: ButtonThreshold,
+        action: impl ToString,
+    ) -> &mut Self {
+        self.bind_gamepad_axis_move(scenario, axis, action)
+            .set_input_threshold(scenario, InputBinding::GamepadAxis(axis), threshold)
+    }
+    /// Every bound action name, sorted and without duplicates.
     pub fn list_all_actions(&self) -> Vec<String> {
-        let mut actions = vec![];
-        if !self.keyboard_binding.is_empty() {
-            for x in self.keyboard_binding.values() {
-                actions.push(x.to_owned());
-            }
-        }
-        if !self.mouse_button_binding.is_empty() {
-            for x in self.mouse_button_binding.values() {
-                actions.push(x.to_owned());
-            }
-        }
-        if !self.mouse_axis_binding.is_empty() {
-            for x in self.mouse_axis_binding.values() {
-                actions.push(x.to_owned());
-            }
-        }
-        if !self.gamepad_button_binding.is_empty() {
-            for x in self.gamepad_button_binding.values() {
-                actions.push(x.to_owned());
-            }
-        }
-        if !self.gamepad_axis_binding.is_empty() {
-            for x in self.gamepad_axis_binding.values() {
-                actions.push(x.to_owned());
-            }
-        }
+        let mut actions = self
+            .bindings()
+            .into_iter()
+            .map(|(_, _, action)| action)
+            .collect::<Vec<_>>();
+        actions.sort();
+        actions.dedup();
         actions
     }
     pub fn list_scenario_actions_keyboard_mouse(&self, scenario: T) -> Vec<String> {
         let mut actions = vec![];
-        if !self.keyboard_binding.is_empty() {
-            for (sc, key) in self.keyboard_binding.keys() {
-                if sc == &scenario {
-                    actions.push(
-                        self.keyboard_binding
-                            .get(&(sc.clone(), key.clone()))
-                            .unwrap()
-                            .to_owned(),
-                    );
-                }
-            }
-        }
-        if !self.mouse_button_binding.is_empty() {
-            for (sc, key) in self.mouse_button_binding.keys() {
-                if sc == &scenario {
-                    actions.push(
-                        self.mouse_button_binding
-                            .get(&(sc.clone(), key.clone()))
-                            .unwrap()
-                            .to_owned(),
-                    );
-                }
-            }
-        }
-        if !self.mouse_axis_binding.is_empty() {
-            for (sc, key) in self.mouse_axis_binding.keys() {
-                if sc == &scenario {
-                    actions.push(
-                        self.mouse_axis_binding
-                            .get(&(sc.clone(), key.clone()))
-                            .unwrap()
-                            .to_owned(),
-                    );
-                }
-            }
-        }
+        actions.extend(
+            self.keyboard_binding
+                .iter()
+                .filter(|((sc, _), _)| sc == &scenario)
+                .map(|(_, action)| action.to_owned()),
+        );
+        actions.extend(
+            self.scancode_binding
+                .iter()
+                .filter(|((sc, _), _)| sc == &scenario)
+                .map(|(_, action)| action.to_owned()),
+        );
+        actions.extend(
+            self.mouse_button_binding
+                .iter()
+                .filter(|((sc, _), _)| sc == &scenario)
+                .map(|(_, action)| action.to_owned()),
+        );
+        actions.extend(
+            self.mouse_axis_binding
+                .iter()
+                .filter(|((sc, _), _)| sc == &scenario)
+                .map(|(_, action)| action.to_owned()),
+        );
         actions
     }
-    fn list_scenario_actions_gamepad(&self, scenario: T) -> Vec<String> {
+    pub fn list_scenario_actions_gamepad(&self, scenario: T) -> Vec<String> {
         let mut actions = vec![];
-        if !self.gamepad_button_binding.is_empty() {
-            for (sc, key) in self.gamepad_button_binding.keys() {
-                if sc == &scenario {
-                    actions.push(
-                        self.gamepad_button_binding
-                            .get(&(sc.clone(), key.clone()))
-                            .unwrap()
-                            .to_owned(),
-                    );
-                }
-            }
-        }
-        if !self.gamepad_axis_binding.is_empty() {
-            for (sc, key) in self.gamepad_axis_binding.keys() {
-                if sc == &scenario {
-                    actions.push(
-                        self.gamepad_axis_binding
-                            .get(&(sc.clone(), key.clone()))
-                            .unwrap()
-                            .to_owned(),
-                    );
-                }
-            }
-        }
+        actions.extend(
+            self.gamepad_button_binding
+                .iter()
+                .filter(|((sc, _), _)| sc == &scenario)
+                .map(|(_, action)| action.to_owned()),
+        );
+        actions.extend(
+            self.gamepad_axis_binding
+                .iter()
+                .filter(|((sc, _), _)| sc == &scenario)
+                .map(|(_, action)| action.to_owned()),
+        );
         actions
     }
 }
@@ -232,21 +298,80 @@ where
             .add_event::<InputActionStarted>()
             .add_event::<InputActionContinuing>()
             .add_event::<InputActionFinished>()
-            .add_systems(Update, InputMapper::<T>::event_cycle)
-            .add_systems(Update, InputMapper::<T>::keyboard_key_press_system)
+            .add_event::<RebindCompleted<T>>()
+            .add_event::<RebindCancelled<T>>()
+            .add_event::<MenuNavigation>()
+            .add_event::<PlayerDeviceLost>()
+            .add_systems(
+                Update,
+                InputMapper::<T>::event_cycle
+                    .in_set(ActionEventSystem)
+                    .after(DeviceInputSystem),
+            )
+            .add_systems(
+                Update,
+                (
+                    ActionState::tick_system,
+                    InputMapper::<T>::action_state_system,
+                )
+                    .chain()
+                    .after(ActionEventSystem),
+            )
+            .add_systems(
+                PreUpdate,
+                InputMapper::<T>::focus_loss_system
+                    .after(InputSystem)
+                    // `WindowFocused` is only registered by `WindowPlugin`, headless apps don't have it.
+                    .run_if(resource_exists::<Events<WindowFocused>>()),
+            )
+            .add_systems(Update, InputMapper::<T>::text_capture_system)
+            .add_systems(Update, InputMapper::<T>::rebind_system)
+            .add_systems(Update, InputMapper::<T>::gamepad_family_system)
+            .add_systems(
+                Update,
+                InputMapper::<T>::gamepad_disconnect_system.before(ActionEventSystem),
+            )
+            .add_systems(
+                Update,
+                InputMapper::<T>::rumble_system.after(ActionEventSystem),
+            )
+            .add_systems(
+                Update,
+                InputMapper::<T>::menu_navigation_system.after(ActionEventSystem),
+            )
+            .add_systems(Update, InputMapper::<T>::cursor_grab_system)
+            .add_systems(
+                FixedUpdate,
+                InputMapper::<T>::fixed_tick_system.in_set(FixedInputSystem),
+            )
+            .add_systems(
+                Update,
+                (
+                    InputMapper::<T>::keyboard_key_press_system,
+                    InputMapper::<T>::keyboard_scancode_press_system,
+                    InputMapper::<T>::keyboard_layout_system,
+                )
+                    .run_if(InputMapper::<T>::is_mapping)
+                    .in_set(DeviceInputSystem),
+            )
             .add_systems(
                 Update,
                 (
                     InputMapper::<T>::mouse_button_press_system,
                     InputMapper::<T>::mouse_axis_move_system,
-                ),
+                    InputMapper::<T>::pointer_system,
+                )
+                    .run_if(InputMapper::<T>::is_mapping)
+                    .in_set(DeviceInputSystem),
             )
             .add_systems(
                 Update,
                 (
                     InputMapper::<T>::gamepad_button_press_system,
                     InputMapper::<T>::gamepad_axis_move_system,
-                ),
+                )
+                    .run_if(InputMapper::<T>::is_mapping)
+                    .in_set(DeviceInputSystem),
             );
     }
 }
@@ -0,0 +1,248 @@
+//! Drives an [`InputMapper`] without windows or devices, for headless tests.
+//! Build an app with [`MinimalPlugins`], call [`InputMapperTestExt::add_input_mapper_test_plugins`],
+//! then press inputs, advance frames and check [`RecordedActions`] or polled values.
+
+use bevy::{
+    input::{
+        gamepad::{
+            GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnection,
+            GamepadConnectionEvent, GamepadEvent, GamepadInfo,
+        },
+        keyboard::KeyboardInput,
+        mouse::{MouseButtonInput, MouseMotion},
+        ButtonState, InputPlugin,
+    },
+    prelude::*,
+};
+
+use crate::{
+    input::events::{InputActionContinuing, InputActionFinished, InputActionStarted},
+    InputMapper, InputMapperPlugin,
+};
+
+/// Action events recorded since the last [`InputMapperTestExt::take_recorded_actions`].
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct RecordedActions {
+    pub started: Vec<(String, f32)>,
+    pub continuing: Vec<(String, f32)>,
+    pub finished: Vec<String>,
+}
+
+impl RecordedActions {
+    pub fn was_started(&self, action: &str) -> bool {
+        self.started.iter().any(|(name, _)| name == action)
+    }
+    pub fn was_finished(&self, action: &str) -> bool {
+        self.finished.iter().any(|name| name == action)
+    }
+}
+
+fn record_actions_system(
+    mut recorded: ResMut<RecordedActions>,
+    mut action_started: EventReader<InputActionStarted>,
+    mut action_continuing: EventReader<InputActionContinuing>,
+    mut action_finished: EventReader<InputActionFinished>,
+) {
+    for ev in action_started.iter() {
+        recorded.started.push((ev.0.to_owned(), ev.1));
+    }
+    for ev in action_continuing.iter() {
+        recorded.continuing.push((ev.0.to_owned(), ev.1));
+    }
+    for ev in action_finished.iter() {
+        recorded.finished.push(ev.0.to_owned());
+    }
+}
+
+/// Simulates devices on an [`App`], see the [module documentation](self).
+pub trait InputMapperTestExt {
+    /// Adds Bevy's input handling, the scenario state, the [`InputMapperPlugin`]
+    /// and recording of action events.
+    fn add_input_mapper_test_plugins<T: Copy + States>(&mut self) -> &mut Self;
+    /// Switches to the scenario on the next frame.
+    fn set_scenario<T: Copy + States>(&mut self, scenario: T) -> &mut Self;
+    /// Calls `f` with the mapper, e.g. to bind inputs.
+    fn with_input_mapper<T: Copy + States>(
+        &mut self,
+        f: impl FnOnce(&mut InputMapper<T>),
+    ) -> &mut Self;
+    /// Presses a key, `scan_code` being the physical key that produces it on the simulated layout.
+    fn press_key(&mut self, key: KeyCode, scan_code: ScanCode) -> &mut Self;
+    fn release_key(&mut self, key: KeyCode, scan_code: ScanCode) -> &mut Self;
+    fn press_scancode(&mut self, scan_code: ScanCode) -> &mut Self;
+    fn release_scancode(&mut self, scan_code: ScanCode) -> &mut Self;
+    fn press_mouse_button(&mut self, button: MouseButton) -> &mut Self;
+    fn release_mouse_button(&mut self, button: MouseButton) -> &mut Self;
+    fn move_mouse(&mut self, delta: Vec2) -> &mut Self;
+    fn connect_gamepad(&mut self, gamepad: Gamepad, name: &str) -> &mut Self;
+    fn disconnect_gamepad(&mut self, gamepad: Gamepad) -> &mut Self;
+    /// Sets a gamepad button's value, from 0 to 1.
+    fn set_gamepad_button(
+        &mut self,
+        gamepad: Gamepad,
+        button: GamepadButtonType,
+        value: f32,
+    ) -> &mut Self;
+    /// Sets a gamepad axis' value, from -1 to 1.
+    fn set_gamepad_axis(
+        &mut self,
+        gamepad: Gamepad,
+        axis: GamepadAxisType,
+        value: f32,
+    ) -> &mut Self;
+    /// Runs the app's schedules `frames` times.
+    fn advance_frames(&mut self, frames: usize) -> &mut Self;
+    /// Returns and clears the action events recorded so far.
+    fn take_recorded_actions(&mut self) -> RecordedActions;
+    /// The action's current value in the scenario.
+    fn action_value<T: Copy + States>(&self, scenario: T, action: &str) -> f32;
+}
+
+impl InputMapperTestExt for App {
+    fn add_input_mapper_test_plugins<T: Copy + States>(&mut self) -> &mut Self {
+        self.add_plugins(InputPlugin)
+            .add_state::<T>()
+            .add_plugins(InputMapperPlugin::<T>::new())
+            .init_resource::<RecordedActions>()
+            .add_systems(Last, record_actions_system)
+    }
+
+    fn set_scenario<T: Copy + States>(&mut self, scenario: T) -> &mut Self {
+        self.world.resource_mut::<NextState<T>>().set(scenario);
+        self
+    }
+
+    fn with_input_mapper<T: Copy + States>(
+        &mut self,
+        f: impl FnOnce(&mut InputMapper<T>),
+    ) -> &mut Self {
+        f(&mut self.world.resource_mut::<InputMapper<T>>());
+        self
+    }
+
+    fn press_key(&mut self, key: KeyCode, scan_code: ScanCode) -> &mut Self {
+        self.world.send_event(KeyboardInput {
+            scan_code: scan_code.0,
+            key_code: Some(key),
+            state: ButtonState::Pressed,
+            window: Entity::PLACEHOLDER,
+        });
+        self
+    }
+
+    fn release_key(&mut self, key: KeyCode, scan_code: ScanCode) -> &mut Self {
+        self.world.send_event(KeyboardInput {
+            scan_code: scan_code.0,
+            key_code: Some(key),
+            state: ButtonState::Released,
+            window: Entity::PLACEHOLDER,
+        });
+        self
+    }
+
+    fn press_scancode(&mut self, scan_code: ScanCode) -> &mut Self {
+        self.world.send_event(KeyboardInput {
+            scan_code: scan_code.0,
+            key_code: None,
+            state: ButtonState::Pressed,
+            window: Entity::PLACEHOLDER,
+        });
+        self
+    }
+
+    fn release_scancode(&mut self, scan_code: ScanCode) -> &mut Self {
+        self.world.send_event(KeyboardInput {
+            scan_code: scan_code.0,
+            key_code: None,
+            state: ButtonState::Released,
+            window: Entity::PLACEHOLDER,
+        });
+        self
+    }
+
+    fn press_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        self.world.send_event(MouseButtonInput {
+            button,
+            state: ButtonState::Pressed,
+            window: Entity::PLACEHOLDER,
+        });
+        self
+    }
+
+    fn release_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        self.world.send_event(MouseButtonInput {
+            button,
+            state: ButtonState::Released,
+            window: Entity::PLACEHOLDER,
+        });
+        self
+    }
+
+    fn move_mouse(&mut self, delta: Vec2) -> &mut Self {
+        self.world.send_event(MouseMotion { delta });
+        self
+    }
+
+    fn connect_gamepad(&mut self, gamepad: Gamepad, name: &str) -> &mut Self {
+        self.world
+            .send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+                gamepad,
+                GamepadConnection::Connected(GamepadInfo {
+                    name: name.to_owned(),
+                }),
+            )));
+        self
+    }
+
+    fn disconnect_gamepad(&mut self, gamepad: Gamepad) -> &mut Self {
+        self.world
+            .send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+                gamepad,
+                GamepadConnection::Disconnected,
+            )));
+        self
+    }
+
+    fn set_gamepad_button(
+        &mut self,
+        gamepad: Gamepad,
+        button: GamepadButtonType,
+        value: f32,
+    ) -> &mut Self {
+        self.world
+            .send_event(GamepadEvent::Button(GamepadButtonChangedEvent::new(
+                gamepad, button, value,
+            )));
+        self
+    }
+
+    fn set_gamepad_axis(
+        &mut self,
+        gamepad: Gamepad,
+        axis: GamepadAxisType,
+        value: f32,
+    ) -> &mut Self {
+        self.world
+            .send_event(GamepadEvent::Axis(GamepadAxisChangedEvent::new(
+                gamepad, axis, value,
+            )));
+        self
+    }
+
+    fn advance_frames(&mut self, frames: usize) -> &mut Self {
+        for _ in 0..frames {
+            self.update();
+        }
+        self
+    }
+
+    fn take_recorded_actions(&mut self) -> RecordedActions {
+        std::mem::take(&mut *self.world.resource_mut::<RecordedActions>())
+    }
+
+    fn action_value<T: Copy + States>(&self, scenario: T, action: &str) -> f32 {
+        self.world
+            .resource::<InputMapper<T>>()
+            .value(scenario, action)
+    }
+}
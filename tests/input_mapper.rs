@@ -0,0 +1,1112 @@
+use std::time::Duration;
+
+use bevy::{
+    input::gamepad::{GamepadButtonType, GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+    time::TimeUpdateStrategy,
+    window::{CursorGrabMode, PrimaryWindow, WindowFocused},
+};
+use bevy_input_mapper::{
+    input::{
+        accessibility::{AutoRepeat, PressMode},
+        action::{ActionInfo, ActionKind},
+        action_state::{ActionState, InputMap},
+        binding::{InputBinding, InputDevice},
+        defaults::BindingOverrides,
+        display::GamepadFamily,
+        events::{PlayerDeviceLost, RebindCancelled, RebindCompleted},
+        fixed::{FixedActionState, FixedInputSystem},
+        gamepad::GamepadAxis as AxisHalf,
+        mouse::MouseAxis,
+        navigation::{ButtonThreshold, MenuAction, MenuNavigation, MenuNavigationOptions},
+        pointer::PointerSpace,
+        rebind::RebindOptions,
+        replay::{InputRecording, RecordingParseError},
+        rumble::RumblePattern,
+        semantic::SemanticButton,
+        snapshot::{ActionSnapshot, SnapshotDelta},
+        suspend::TextCaptureFocus,
+    },
+    testing::{InputMapperTestExt, RecordedActions},
+    InputMapper,
+};
+
+#[derive(
+    States, Default, Debug, Clone, Copy, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize,
+)]
+enum Scenario {
+    #[default]
+    Default,
+    Menu,
+}
+
+/// Scan code of Space on a US layout.
+const SPACE: ScanCode = ScanCode(57);
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_input_mapper_test_plugins::<Scenario>();
+    app
+}
+
+#[test]
+fn key_press_starts_action() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump");
+    });
+    app.press_key(KeyCode::Space, SPACE).advance_frames(2);
+    let recorded = app.take_recorded_actions();
+    assert!(recorded.was_started("jump"));
+    assert_eq!(app.action_value(Scenario::Default, "jump"), 1.);
+    assert_eq!(
+        app.world
+            .resource::<InputMapper<Scenario>>()
+            .scancode_label(SPACE),
+        Some(KeyCode::Space)
+    );
+}
+
+#[test]
+fn mouse_button_press_and_release() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_mouse_button_press(Scenario::Default, MouseButton::Left, "fire");
+    });
+    app.press_mouse_button(MouseButton::Left).advance_frames(2);
+    assert!(app.take_recorded_actions().was_started("fire"));
+    app.release_mouse_button(MouseButton::Left)
+        .advance_frames(2);
+    assert!(app.take_recorded_actions().was_finished("fire"));
+    assert_eq!(app.action_value(Scenario::Default, "fire"), 0.);
+}
+
+#[test]
+fn gamepad_button_event() {
+    let mut app = app();
+    let gamepad = Gamepad::new(0);
+    app.connect_gamepad(gamepad, "Xbox Controller")
+        .advance_frames(1)
+        .with_input_mapper::<Scenario>(|im| {
+            im.bind_gamepad_button_press(Scenario::Default, GamepadButtonType::South, "jump");
+        });
+    app.set_gamepad_button(gamepad, GamepadButtonType::South, 1.)
+        .advance_frames(2);
+    assert!(app.take_recorded_actions().was_started("jump"));
+    app.set_gamepad_button(gamepad, GamepadButtonType::South, 0.)
+        .advance_frames(2);
+    assert!(app.take_recorded_actions().was_finished("jump"));
+}
+
+#[test]
+fn gamepad_axis_event() {
+    let mut app = app();
+    let gamepad = Gamepad::new(0);
+    app.connect_gamepad(gamepad, "Xbox Controller")
+        .advance_frames(1)
+        .with_input_mapper::<Scenario>(|im| {
+            im.bind_gamepad_axis_move(
+                Scenario::Default,
+                AxisHalf::PositiveLeftStickX,
+                "move_right",
+            )
+            .bind_gamepad_axis_move(
+                Scenario::Default,
+                AxisHalf::NegativeLeftStickX,
+                "move_left",
+            );
+        });
+    app.set_gamepad_axis(gamepad, GamepadAxisType::LeftStickX, -0.8)
+        .advance_frames(2);
+    let recorded = app.take_recorded_actions();
+    assert!(recorded.was_started("move_left"));
+    assert!(!recorded.was_started("move_right"));
+    assert!(app.action_value(Scenario::Default, "move_left") > 0.7);
+}
+
+#[test]
+fn focus_loss_releases_held_actions() {
+    let mut app = app();
+    app.add_event::<WindowFocused>();
+    let window = app.world.spawn((Window::default(), PrimaryWindow)).id();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump")
+            .bind_keyboard_key_press(Scenario::Default, KeyCode::ShiftLeft, "auto_run")
+            .set_release_on_focus_loss("auto_run", false);
+    });
+    app.press_key(KeyCode::Space, SPACE)
+        .press_key(KeyCode::ShiftLeft, ScanCode(42))
+        .advance_frames(2);
+    app.take_recorded_actions();
+    app.world.send_event(WindowFocused {
+        window,
+        focused: false,
+    });
+    app.advance_frames(2);
+    let recorded = app.take_recorded_actions();
+    assert!(recorded.was_finished("jump"));
+    assert!(!recorded.was_finished("auto_run"));
+    assert_eq!(app.action_value(Scenario::Default, "jump"), 0.);
+    assert_eq!(app.action_value(Scenario::Default, "auto_run"), 1.);
+}
+
+#[test]
+fn disconnect_releases_only_the_lost_gamepad() {
+    let mut app = app();
+    let (first, second) = (Gamepad::new(0), Gamepad::new(1));
+    app.connect_gamepad(first, "Xbox Controller")
+        .connect_gamepad(second, "Xbox Controller")
+        .advance_frames(1)
+        .with_input_mapper::<Scenario>(|im| {
+            im.bind_gamepad_button_press(Scenario::Default, GamepadButtonType::South, "jump")
+                .bind_gamepad_button_press(Scenario::Default, GamepadButtonType::East, "dodge");
+        });
+    app.set_gamepad_button(first, GamepadButtonType::South, 1.)
+        .set_gamepad_button(second, GamepadButtonType::South, 1.)
+        .set_gamepad_button(second, GamepadButtonType::East, 1.)
+        .advance_frames(2);
+    app.take_recorded_actions();
+    app.disconnect_gamepad(second).advance_frames(2);
+    let recorded = app.take_recorded_actions();
+    assert!(!recorded.was_finished("jump"));
+    assert!(recorded.was_finished("dodge"));
+    assert_eq!(app.action_value(Scenario::Default, "jump"), 1.);
+    let lost: Vec<PlayerDeviceLost> = app
+        .world
+        .resource_mut::<Events<PlayerDeviceLost>>()
+        .drain()
+        .collect();
+    assert_eq!(lost.len(), 1);
+    assert_eq!(lost[0].gamepad, second);
+    assert_eq!(lost[0].actions, vec!["dodge".to_owned(), "jump".to_owned()]);
+}
+
+#[test]
+fn rumble_follows_the_device_that_pressed_the_action() {
+    let mut app = app();
+    let gamepad = Gamepad::new(0);
+    app.connect_gamepad(gamepad, "Xbox Controller")
+        .advance_frames(1)
+        .with_input_mapper::<Scenario>(|im| {
+            im.bind_gamepad_button_press(Scenario::Default, GamepadButtonType::South, "jump")
+                .bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump")
+                .set_action_rumble(
+                    "jump",
+                    RumblePattern {
+                        intensity: GamepadRumbleIntensity::MAX,
+                        duration: Duration::from_millis(100),
+                    },
+                );
+        });
+    app.set_gamepad_button(gamepad, GamepadButtonType::South, 1.)
+        .advance_frames(2);
+    let rumbles = app
+        .world
+        .resource_mut::<Events<GamepadRumbleRequest>>()
+        .drain()
+        .count();
+    assert_eq!(rumbles, 1);
+    app.set_gamepad_button(gamepad, GamepadButtonType::South, 0.)
+        .advance_frames(2);
+    app.press_key(KeyCode::Space, SPACE).advance_frames(2);
+    assert!(app.take_recorded_actions().was_started("jump"));
+    let rumbles = app
+        .world
+        .resource_mut::<Events<GamepadRumbleRequest>>()
+        .drain()
+        .count();
+    assert_eq!(rumbles, 0);
+}
+
+#[test]
+fn entity_action_state_follows_press_modes() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump")
+            .set_press_mode("jump", PressMode::Toggle);
+    });
+    let player = app
+        .world
+        .spawn((InputMap::keyboard_mouse(), ActionState::default()))
+        .id();
+    app.press_key(KeyCode::Space, SPACE)
+        .advance_frames(1)
+        .release_key(KeyCode::Space, SPACE)
+        .advance_frames(2);
+    let action_state = app.world.get::<ActionState>(player).unwrap();
+    assert_eq!(app.action_value(Scenario::Default, "jump"), 1.);
+    assert_eq!(action_state.value("jump"), 1.);
+}
+
+#[test]
+fn entity_action_state_reads_only_its_gamepad() {
+    let mut app = app();
+    let (first, second) = (Gamepad::new(0), Gamepad::new(1));
+    app.connect_gamepad(first, "Xbox Controller")
+        .connect_gamepad(second, "Xbox Controller")
+        .advance_frames(1)
+        .with_input_mapper::<Scenario>(|im| {
+            im.bind_gamepad_button_press(Scenario::Default, GamepadButtonType::South, "jump");
+        });
+    let player_one = app
+        .world
+        .spawn((InputMap::gamepad(first), ActionState::default()))
+        .id();
+    let player_two = app
+        .world
+        .spawn((InputMap::gamepad(second), ActionState::default()))
+        .id();
+    app.set_gamepad_button(second, GamepadButtonType::South, 1.)
+        .advance_frames(1);
+    assert!(!app
+        .world
+        .get::<ActionState>(player_one)
+        .unwrap()
+        .pressed("jump"));
+    assert!(app
+        .world
+        .get::<ActionState>(player_two)
+        .unwrap()
+        .just_pressed("jump"));
+}
+
+#[test]
+fn applied_snapshots_skip_press_modes() {
+    let setup = |im: &mut InputMapper<Scenario>| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::ShiftLeft, "sprint")
+            .register_action("sprint", ActionInfo::default())
+            .set_press_mode("sprint", PressMode::Toggle);
+    };
+    let (mut local, mut remote) = (app(), app());
+    local.with_input_mapper(setup);
+    remote.with_input_mapper(setup);
+    let sync = |local: &mut App, remote: &mut App| {
+        let im = local.world.resource::<InputMapper<Scenario>>();
+        let layout = im.snapshot_layout();
+        let snapshot = im.snapshot(Scenario::Default, &layout);
+        remote.with_input_mapper::<Scenario>(|im| {
+            im.apply_snapshot(Scenario::Default, &layout, &snapshot);
+        });
+        remote.advance_frames(1);
+    };
+    for _ in 0..2 {
+        local
+            .press_key(KeyCode::ShiftLeft, ScanCode(42))
+            .advance_frames(1);
+        sync(&mut local, &mut remote);
+        local
+            .release_key(KeyCode::ShiftLeft, ScanCode(42))
+            .advance_frames(1);
+        sync(&mut local, &mut remote);
+        assert_eq!(
+            remote.action_value(Scenario::Default, "sprint"),
+            local.action_value(Scenario::Default, "sprint")
+        );
+    }
+    assert_eq!(remote.action_value(Scenario::Default, "sprint"), 0.);
+}
+
+#[test]
+fn menu_focus_wraps() {
+    assert_eq!(MenuAction::Down.step_focus(2, 3, true), 0);
+    assert_eq!(MenuAction::Down.step_focus(2, 3, false), 2);
+    assert_eq!(MenuAction::Up.step_focus(0, 3, true), 2);
+    assert_eq!(MenuAction::PreviousTab.step_focus(1, 3, true), 0);
+    assert_eq!(MenuAction::Confirm.step_focus(1, 3, true), 1);
+    assert_eq!(MenuAction::Right.step_focus(0, 0, true), 0);
+}
+
+#[test]
+fn menu_previous_tab_has_a_key() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_menu_navigation(Scenario::Default, MenuNavigationOptions::default());
+    });
+    app.press_key(KeyCode::PageUp, ScanCode(104))
+        .advance_frames(2);
+    let navigation: Vec<MenuNavigation> = app
+        .world
+        .resource_mut::<Events<MenuNavigation>>()
+        .drain()
+        .collect();
+    assert_eq!(navigation.len(), 1);
+    assert_eq!(navigation[0].0, MenuAction::PreviousTab);
+}
+
+#[test]
+fn release_forgets_the_trigger_was_pressed() {
+    let mut app = app();
+    let gamepad = Gamepad::new(0);
+    app.connect_gamepad(gamepad, "Xbox Controller")
+        .advance_frames(1)
+        .with_input_mapper::<Scenario>(|im| {
+            im.bind_gamepad_trigger_press(
+                Scenario::Default,
+                GamepadButtonType::RightTrigger2,
+                ButtonThreshold {
+                    press: 0.5,
+                    release: 0.3,
+                },
+                "fire",
+            );
+        });
+    app.set_gamepad_button(gamepad, GamepadButtonType::RightTrigger2, 0.6)
+        .advance_frames(2);
+    assert_eq!(app.action_value(Scenario::Default, "fire"), 1.);
+    app.with_input_mapper::<Scenario>(|im| {
+        im.release(Scenario::Default, "fire");
+    });
+    app.set_gamepad_button(gamepad, GamepadButtonType::RightTrigger2, 0.4)
+        .advance_frames(2);
+    assert_eq!(app.action_value(Scenario::Default, "fire"), 0.);
+}
+
+#[test]
+fn menu_stick_presses_past_the_threshold() {
+    let mut app = app();
+    let options = MenuNavigationOptions::default();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_menu_navigation(Scenario::Default, options.clone());
+        assert_eq!(
+            im.input_threshold(
+                Scenario::Default,
+                &InputBinding::GamepadAxis(AxisHalf::NegativeLeftStickY)
+            ),
+            Some(options.stick)
+        );
+    });
+    let gamepad = Gamepad::new(0);
+    app.connect_gamepad(gamepad, "Xbox Controller")
+        .advance_frames(1)
+        .set_gamepad_axis(gamepad, GamepadAxisType::LeftStickY, -0.5)
+        .advance_frames(2);
+    assert_eq!(
+        app.action_value(Scenario::Default, MenuAction::Down.action_name()),
+        0.
+    );
+    app.set_gamepad_axis(gamepad, GamepadAxisType::LeftStickY, -0.7)
+        .advance_frames(1);
+    assert_eq!(
+        app.action_value(Scenario::Default, MenuAction::Down.action_name()),
+        1.
+    );
+}
+
+#[test]
+fn clearing_cursor_grab_releases_the_cursor() {
+    let mut app = app();
+    let window = app.world.spawn((Window::default(), PrimaryWindow)).id();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_mouse_axis_move(Scenario::Default, MouseAxis::PositiveX, "look_right")
+            .set_cursor_grab(Some(CursorGrabMode::Locked));
+    });
+    app.advance_frames(2);
+    let cursor = app.world.get::<Window>(window).unwrap().cursor;
+    assert_eq!(cursor.grab_mode, CursorGrabMode::Locked);
+    assert!(!cursor.visible);
+    app.with_input_mapper::<Scenario>(|im| {
+        im.set_cursor_grab(None);
+    });
+    app.advance_frames(1);
+    let cursor = app.world.get::<Window>(window).unwrap().cursor;
+    assert_eq!(cursor.grab_mode, CursorGrabMode::None);
+    assert!(cursor.visible);
+}
+
+#[test]
+fn trigger_threshold_survives_reset_to_defaults() {
+    let mut app = app();
+    let trigger = InputBinding::GamepadButton(GamepadButtonType::RightTrigger2);
+    let threshold = ButtonThreshold {
+        press: 0.5,
+        release: 0.3,
+    };
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_gamepad_trigger_press(
+            Scenario::Default,
+            GamepadButtonType::RightTrigger2,
+            threshold,
+            "fire",
+        )
+        .set_defaults()
+        .unbind_input(Scenario::Default, trigger)
+        .apply_overrides(&BindingOverrides::default());
+        assert_eq!(
+            im.input_threshold(Scenario::Default, &trigger),
+            Some(threshold)
+        );
+    });
+    let gamepad = Gamepad::new(0);
+    app.connect_gamepad(gamepad, "Xbox Controller")
+        .advance_frames(1)
+        .set_gamepad_button(gamepad, GamepadButtonType::RightTrigger2, 0.2)
+        .advance_frames(2);
+    assert_eq!(app.action_value(Scenario::Default, "fire"), 0.);
+    app.set_gamepad_button(gamepad, GamepadButtonType::RightTrigger2, 0.6)
+        .advance_frames(1);
+    assert_eq!(app.action_value(Scenario::Default, "fire"), 1.);
+}
+
+#[test]
+fn scancode_binding_follows_the_physical_key() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_scancode_press(Scenario::Default, SPACE, "jump");
+    });
+    app.press_key(KeyCode::Q, ScanCode(16)).advance_frames(2);
+    assert!(!app.take_recorded_actions().was_started("jump"));
+    app.press_key(KeyCode::Q, SPACE).advance_frames(2);
+    assert!(app.take_recorded_actions().was_started("jump"));
+    app.release_key(KeyCode::Q, SPACE).advance_frames(2);
+    assert!(app.take_recorded_actions().was_finished("jump"));
+    app.with_input_mapper::<Scenario>(|im| {
+        assert_eq!(im.scancode_label(SPACE), Some(KeyCode::Q));
+    });
+}
+
+#[test]
+fn suspend_releases_and_ignores_inputs() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump")
+            .bind_keyboard_key_press(Scenario::Default, KeyCode::E, "use");
+    });
+    app.press_key(KeyCode::Space, SPACE).advance_frames(2);
+    app.take_recorded_actions();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.suspend();
+    });
+    app.press_key(KeyCode::E, ScanCode(18)).advance_frames(2);
+    let recorded = app.take_recorded_actions();
+    assert!(recorded.was_finished("jump"));
+    assert!(!recorded.was_started("use"));
+    app.with_input_mapper::<Scenario>(|im| {
+        im.resume();
+    });
+    app.advance_frames(2);
+    assert!(app.take_recorded_actions().was_started("use"));
+}
+
+#[test]
+fn text_capture_suspends_while_focused() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::E, "use")
+            .set_auto_text_capture(true);
+    });
+    let chat = app.world.spawn(TextCaptureFocus).id();
+    app.advance_frames(1)
+        .press_key(KeyCode::E, ScanCode(18))
+        .advance_frames(2);
+    assert!(app.world.resource::<InputMapper<Scenario>>().is_suspended());
+    assert!(!app.take_recorded_actions().was_started("use"));
+    app.world.despawn(chat);
+    app.advance_frames(2);
+    assert!(!app.world.resource::<InputMapper<Scenario>>().is_suspended());
+    assert!(app.take_recorded_actions().was_started("use"));
+}
+
+fn rebind_results(app: &mut App) -> Vec<RebindCompleted<Scenario>> {
+    app.world
+        .resource_mut::<Events<RebindCompleted<Scenario>>>()
+        .drain()
+        .collect()
+}
+
+#[test]
+fn rebind_captures_the_next_input() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump")
+            .bind_keyboard_key_press(Scenario::Default, KeyCode::F, "use");
+        assert!(im.start_rebind(Scenario::Default, "jump", RebindOptions::default()));
+    });
+    app.press_key(KeyCode::F, ScanCode(33)).advance_frames(2);
+    let completed = rebind_results(&mut app);
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].input, InputBinding::Keyboard(KeyCode::F));
+    assert_eq!(completed[0].conflict.as_deref(), Some("use"));
+    app.with_input_mapper::<Scenario>(|im| {
+        assert_eq!(im.rebinding_action(), None);
+        assert_eq!(
+            im.bound_inputs(Scenario::Default, "jump"),
+            vec![InputBinding::Keyboard(KeyCode::F)]
+        );
+    });
+    // The captured key is still held, it only presses the action once pressed again.
+    app.advance_frames(2);
+    assert_eq!(app.action_value(Scenario::Default, "jump"), 0.);
+    app.release_key(KeyCode::F, ScanCode(33))
+        .advance_frames(1)
+        .press_key(KeyCode::F, ScanCode(33))
+        .advance_frames(2);
+    assert_eq!(app.action_value(Scenario::Default, "jump"), 1.);
+}
+
+#[test]
+fn rebind_cancel_key_does_not_press_its_action() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::Escape, "pause");
+        im.start_rebind(Scenario::Default, "jump", RebindOptions::default());
+    });
+    app.press_key(KeyCode::Escape, ScanCode(1))
+        .advance_frames(2);
+    let cancelled: Vec<RebindCancelled<Scenario>> = app
+        .world
+        .resource_mut::<Events<RebindCancelled<Scenario>>>()
+        .drain()
+        .collect();
+    assert_eq!(cancelled.len(), 1);
+    app.advance_frames(1);
+    assert!(!app.take_recorded_actions().was_started("pause"));
+    app.release_key(KeyCode::Escape, ScanCode(1))
+        .advance_frames(1)
+        .press_key(KeyCode::Escape, ScanCode(1))
+        .advance_frames(2);
+    assert!(app.take_recorded_actions().was_started("pause"));
+}
+
+#[test]
+fn rebind_holds_only_the_capturing_gamepad() {
+    let mut app = app();
+    let (first, second) = (Gamepad::new(0), Gamepad::new(1));
+    app.connect_gamepad(first, "Xbox Wireless Controller")
+        .connect_gamepad(second, "Xbox Wireless Controller")
+        .advance_frames(1)
+        .with_input_mapper::<Scenario>(|im| {
+            im.start_rebind(Scenario::Default, "jump", RebindOptions::default());
+        });
+    app.set_gamepad_button(first, GamepadButtonType::South, 1.)
+        .advance_frames(2);
+    assert_eq!(rebind_results(&mut app).len(), 1);
+    app.set_gamepad_button(second, GamepadButtonType::South, 1.)
+        .advance_frames(2);
+    assert!(app.take_recorded_actions().was_started("jump"));
+}
+
+#[test]
+fn rebind_captures_mouse_motion_by_default() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.start_rebind(Scenario::Default, "look_left", RebindOptions::default());
+    });
+    app.move_mouse(Vec2::new(-5., 0.)).advance_frames(1);
+    assert!(rebind_results(&mut app).is_empty());
+    app.move_mouse(Vec2::new(-30., 0.)).advance_frames(1);
+    let completed = rebind_results(&mut app);
+    assert_eq!(completed.len(), 1);
+    assert_eq!(
+        completed[0].input,
+        InputBinding::MouseAxis(MouseAxis::NegativeX)
+    );
+}
+
+#[test]
+fn start_rebind_refuses_without_devices() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.register_action(
+            "pause",
+            ActionInfo {
+                rebindable: false,
+                ..default()
+            },
+        )
+        .register_action(
+            "aim",
+            ActionInfo {
+                devices: vec![InputDevice::Gamepad],
+                ..default()
+            },
+        );
+        assert!(!im.start_rebind(Scenario::Default, "pause", RebindOptions::default()));
+        let keyboard_only = RebindOptions {
+            devices: vec![InputDevice::Keyboard],
+            ..default()
+        };
+        assert!(!im.start_rebind(Scenario::Default, "aim", keyboard_only));
+        assert_eq!(im.rebinding_action(), None);
+        assert!(!im.is_suspended());
+    });
+}
+
+#[test]
+fn unbinding_releases_held_actions() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump")
+            .bind_keyboard_key_press(Scenario::Default, KeyCode::E, "use");
+    });
+    app.press_key(KeyCode::Space, SPACE)
+        .press_key(KeyCode::E, ScanCode(18))
+        .advance_frames(2);
+    app.take_recorded_actions();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.unbind_input(Scenario::Default, InputBinding::Keyboard(KeyCode::Space))
+            .unbind_action(Scenario::Default, "use");
+        assert!(im.scenario_bindings(Scenario::Default).is_empty());
+    });
+    app.advance_frames(2);
+    let recorded = app.take_recorded_actions();
+    assert!(recorded.was_finished("jump"));
+    assert!(recorded.was_finished("use"));
+    assert_eq!(app.action_value(Scenario::Default, "jump"), 0.);
+}
+
+#[test]
+fn overrides_round_trip_through_serde() {
+    let mut defaults = InputMapper::<Scenario>::default();
+    defaults
+        .bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump")
+        .bind_keyboard_key_press(Scenario::Default, KeyCode::E, "use")
+        .set_defaults();
+    let mut im = defaults.clone();
+    im.unbind_input(Scenario::Default, InputBinding::Keyboard(KeyCode::E))
+        .bind(Scenario::Default, InputBinding::Keyboard(KeyCode::F), "use");
+    let saved = ron::to_string(&im.user_overrides()).unwrap();
+    let overrides: BindingOverrides<Scenario> = ron::from_str(&saved).unwrap();
+    assert_eq!(overrides, im.user_overrides());
+    let mut loaded = defaults.clone();
+    loaded.apply_overrides(&overrides);
+    assert_eq!(
+        loaded.bound_inputs(Scenario::Default, "use"),
+        vec![InputBinding::Keyboard(KeyCode::F)]
+    );
+    assert_eq!(
+        loaded.bound_inputs(Scenario::Default, "jump"),
+        vec![InputBinding::Keyboard(KeyCode::Space)]
+    );
+    assert!(loaded.is_action_overridden(Scenario::Default, "use"));
+    assert!(!loaded.is_action_overridden(Scenario::Default, "jump"));
+}
+
+#[test]
+fn binding_queries_are_sorted() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::W, "move_up")
+            .bind_gamepad_button_press(Scenario::Default, GamepadButtonType::DPadUp, "move_up")
+            .bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump")
+            .bind_keyboard_key_press(Scenario::Menu, KeyCode::Space, "confirm");
+        let bindings = im.scenario_bindings(Scenario::Default);
+        let actions: Vec<&str> = bindings.iter().map(|b| b.action.as_str()).collect();
+        assert_eq!(actions, vec!["jump", "move_up"]);
+        assert_eq!(
+            bindings[1].keyboard,
+            vec![InputBinding::Keyboard(KeyCode::W)]
+        );
+        assert_eq!(
+            bindings[1].gamepad,
+            vec![InputBinding::GamepadButton(GamepadButtonType::DPadUp)]
+        );
+        assert_eq!(
+            im.input_actions(&InputBinding::Keyboard(KeyCode::Space)),
+            vec![
+                (Scenario::Menu, "confirm".to_owned()),
+                (Scenario::Default, "jump".to_owned())
+            ]
+        );
+    });
+}
+
+#[test]
+fn labels_follow_the_gamepad_family() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump")
+            .bind_gamepad_button_press(Scenario::Default, GamepadButtonType::South, "jump");
+        let south = InputBinding::GamepadButton(GamepadButtonType::South);
+        im.set_gamepad_family(GamepadFamily::PlayStation);
+        assert_eq!(im.input_label(&south).name, "Cross");
+        im.set_gamepad_family(GamepadFamily::Xbox);
+        assert_eq!(im.input_label(&south).glyph, "xbox_a");
+        let labels = im.action_input_labels(Scenario::Default, "jump");
+        let names: Vec<&str> = labels.iter().map(|label| label.name.as_str()).collect();
+        assert_eq!(names, vec!["A", "Space"]);
+    });
+}
+
+#[test]
+fn gamepad_family_is_detected_per_gamepad() {
+    assert_eq!(
+        GamepadFamily::from_name("Xbox Wireless Controller"),
+        GamepadFamily::Xbox
+    );
+    assert_eq!(
+        GamepadFamily::from_name("DualSense Wireless Controller"),
+        GamepadFamily::PlayStation
+    );
+    assert_eq!(
+        GamepadFamily::from_name("Switch-style Xbox Controller Adapter"),
+        GamepadFamily::Generic
+    );
+    let mut app = app();
+    let (nintendo, xbox) = (Gamepad::new(0), Gamepad::new(1));
+    app.with_input_mapper::<Scenario>(|im| {
+        im.set_gamepad_family_detection(true)
+            .bind_gamepad_semantic_press(Scenario::Default, SemanticButton::Confirm, "confirm");
+    });
+    app.connect_gamepad(nintendo, "Nintendo Switch Pro Controller")
+        .connect_gamepad(xbox, "Xbox Wireless Controller")
+        .advance_frames(1);
+    app.set_gamepad_button(nintendo, GamepadButtonType::East, 1.)
+        .advance_frames(2);
+    assert!(app.take_recorded_actions().was_started("confirm"));
+    app.set_gamepad_button(nintendo, GamepadButtonType::East, 0.)
+        .set_gamepad_button(xbox, GamepadButtonType::East, 1.)
+        .advance_frames(2);
+    let recorded = app.take_recorded_actions();
+    assert!(recorded.was_finished("confirm"));
+    assert!(!recorded.was_started("confirm"));
+    app.set_gamepad_button(xbox, GamepadButtonType::South, 1.)
+        .advance_frames(2);
+    assert!(app.take_recorded_actions().was_started("confirm"));
+}
+
+/// Plays a fixed input sequence, returning every frame's action events, sorted.
+fn play_sequence(app: &mut App) -> Vec<RecordedActions> {
+    let mut frames = vec![];
+    let mut frame = |app: &mut App| {
+        app.advance_frames(1);
+        let mut recorded = app.take_recorded_actions();
+        recorded.started.sort_by(|a, b| a.0.cmp(&b.0));
+        recorded.continuing.sort_by(|a, b| a.0.cmp(&b.0));
+        recorded.finished.sort();
+        frames.push(recorded);
+    };
+    app.press_key(KeyCode::Space, SPACE);
+    frame(app);
+    app.press_key(KeyCode::E, ScanCode(18));
+    frame(app);
+    frame(app);
+    app.release_key(KeyCode::Space, SPACE);
+    frame(app);
+    app.release_key(KeyCode::E, ScanCode(18));
+    frame(app);
+    frame(app);
+    frames
+}
+
+#[test]
+fn replay_sends_the_recorded_events() {
+    let setup = |im: &mut InputMapper<Scenario>| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump")
+            .bind_keyboard_key_press(Scenario::Default, KeyCode::E, "use\tor\nlook");
+    };
+    let mut live = app();
+    live.with_input_mapper(setup)
+        .with_input_mapper::<Scenario>(|im| {
+            im.start_recording();
+        });
+    let recorded_events = play_sequence(&mut live);
+    let recording = live
+        .world
+        .resource_mut::<InputMapper<Scenario>>()
+        .stop_recording()
+        .unwrap();
+    let text = recording.to_text();
+    let parsed = InputRecording::from_text(&text, |name| {
+        (name == "Default").then_some(Scenario::Default)
+    })
+    .unwrap();
+    assert_eq!(parsed, recording);
+
+    let mut replayed = app();
+    replayed
+        .with_input_mapper(setup)
+        .with_input_mapper::<Scenario>(|im| {
+            im.start_replay(parsed);
+        });
+    let mut replayed_events = vec![];
+    for _ in 0..recorded_events.len() {
+        replayed.advance_frames(1);
+        let mut recorded = replayed.take_recorded_actions();
+        recorded.started.sort_by(|a, b| a.0.cmp(&b.0));
+        recorded.continuing.sort_by(|a, b| a.0.cmp(&b.0));
+        recorded.finished.sort();
+        replayed_events.push(recorded);
+    }
+    assert_eq!(replayed_events, recorded_events);
+}
+
+#[test]
+fn recording_parse_errors_report_the_line() {
+    let parse = |name: &str| (name == "Default").then_some(Scenario::Default);
+    let text = "frame\nDefault\tjump\t1\nframe\nDefault\tjump\n";
+    assert_eq!(
+        InputRecording::from_text(text, parse),
+        Err(RecordingParseError { line: 4 })
+    );
+    assert_eq!(
+        InputRecording::from_text("Default\tjump\t1\n", parse),
+        Err(RecordingParseError { line: 1 })
+    );
+}
+
+#[test]
+fn snapshots_round_trip_through_bytes() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.register_action("jump", ActionInfo::default())
+            .register_action(
+                "move_x",
+                ActionInfo {
+                    kind: ActionKind::Axis,
+                    ..default()
+                },
+            )
+            .set_value(Scenario::Default, "jump", 1.)
+            .set_value(Scenario::Default, "move_x", -0.5);
+    });
+    app.advance_frames(1);
+    let im = app.world.resource::<InputMapper<Scenario>>();
+    let layout = im.snapshot_layout();
+    let snapshot = im.snapshot(Scenario::Default, &layout);
+    assert!(snapshot.is_pressed(0));
+    assert_eq!(snapshot.axis(0), -0.5);
+    let bytes = snapshot.to_bytes();
+    assert_eq!(
+        ActionSnapshot::from_bytes(&bytes, &layout),
+        Some(snapshot.clone())
+    );
+    assert_eq!(ActionSnapshot::from_bytes(&bytes[1..], &layout), None);
+
+    let previous = ActionSnapshot::from_bytes(&[0, 0, 0], &layout).unwrap();
+    let delta = snapshot.diff(&previous);
+    assert!(!delta.is_empty());
+    assert!(snapshot.diff(&snapshot).is_empty());
+    let delta = SnapshotDelta::from_bytes(&delta.to_bytes(), &layout).unwrap();
+    assert_eq!(previous.apply_delta(&delta), snapshot);
+}
+
+/// Advances time by `frame` per frame, with fixed ticks every `fixed`.
+fn timed_app(frame: Duration, fixed: Duration) -> App {
+    let mut app = app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(frame))
+        .insert_resource(FixedTime::new(fixed));
+    app
+}
+
+#[derive(Resource, Default)]
+struct FixedTicks(Vec<FixedActionState>);
+
+fn record_fixed_ticks(im: Res<InputMapper<Scenario>>, mut ticks: ResMut<FixedTicks>) {
+    ticks.0.push(im.fixed_state(Scenario::Default, "jump"));
+}
+
+#[test]
+fn fixed_ticks_see_taps_between_ticks() {
+    let mut app = timed_app(Duration::from_millis(10), Duration::from_millis(50));
+    app.init_resource::<FixedTicks>()
+        .add_systems(FixedUpdate, record_fixed_ticks.after(FixedInputSystem))
+        .with_input_mapper::<Scenario>(|im| {
+            im.bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump");
+        });
+    app.advance_frames(1)
+        .press_key(KeyCode::Space, SPACE)
+        .advance_frames(1)
+        .release_key(KeyCode::Space, SPACE)
+        .advance_frames(12);
+    let ticks = &app.world.resource::<FixedTicks>().0;
+    assert!(ticks.len() >= 2 && ticks.len() < 12);
+    let pressed: Vec<&FixedActionState> = ticks.iter().filter(|tick| tick.just_pressed).collect();
+    assert_eq!(pressed.len(), 1);
+    assert!(pressed[0].pressed());
+    assert!(pressed[0].just_released);
+    assert!(ticks.iter().filter(|tick| tick.pressed()).count() == 1);
+}
+
+#[test]
+fn auto_repeat_presses_again_while_held() {
+    let mut app = timed_app(Duration::from_millis(10), Duration::from_millis(50));
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::Down, "menu_down")
+            .set_auto_repeat(
+                "menu_down",
+                AutoRepeat {
+                    delay: Duration::from_millis(100),
+                    interval: Duration::from_millis(50),
+                },
+            );
+    });
+    app.advance_frames(1)
+        .press_key(KeyCode::Down, ScanCode(108))
+        .advance_frames(30);
+    let started = app
+        .take_recorded_actions()
+        .started
+        .iter()
+        .filter(|(action, _)| action == "menu_down")
+        .count();
+    // The press, then repeats at 100, 150, 200, 250 ms.
+    assert_eq!(started, 5);
+    app.release_key(KeyCode::Down, ScanCode(108))
+        .advance_frames(20);
+    assert!(!app.take_recorded_actions().was_started("menu_down"));
+}
+
+#[test]
+fn hold_as_taps_survives_mashing() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::C, "crouch")
+            .set_press_mode("crouch", PressMode::HoldAsTaps);
+    });
+    for frame in 0..600 {
+        if frame % 2 == 0 {
+            app.press_key(KeyCode::C, ScanCode(46));
+        } else {
+            app.release_key(KeyCode::C, ScanCode(46));
+        }
+        app.advance_frames(1);
+    }
+    app.advance_frames(20);
+    let taps = app
+        .take_recorded_actions()
+        .started
+        .iter()
+        .filter(|(action, _)| action == "crouch")
+        .count();
+    // Every press has its release, so the game's toggle ends up off.
+    assert_eq!(taps % 2, 0);
+    assert_eq!(app.action_value(Scenario::Default, "crouch"), 0.);
+}
+
+#[test]
+fn pointer_values_follow_the_cursor() {
+    let mut app = app();
+    let mut window = Window::default();
+    window.resolution.set(800., 600.);
+    window.set_cursor_position(Some(Vec2::new(200., 150.)));
+    let window = app.world.spawn((window, PrimaryWindow)).id();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_pointer(Scenario::Default, PointerSpace::Window, "cursor")
+            .bind_pointer(
+                Scenario::Default,
+                PointerSpace::Normalized,
+                "cursor_normalized",
+            );
+    });
+    app.advance_frames(1);
+    app.with_input_mapper::<Scenario>(|im| {
+        let value = im.pointer_value(Scenario::Default, "cursor").unwrap();
+        assert_eq!(value.position, Vec3::new(200., 150., 0.));
+        let value = im
+            .pointer_value(Scenario::Default, "cursor_normalized")
+            .unwrap();
+        assert_eq!(value.position, Vec3::new(0.25, 0.25, 0.));
+    });
+    // A minimized window has no size to normalize against.
+    app.world
+        .get_mut::<Window>(window)
+        .unwrap()
+        .resolution
+        .set(0., 0.);
+    app.advance_frames(1);
+    app.with_input_mapper::<Scenario>(|im| {
+        assert_eq!(
+            im.pointer_value(Scenario::Default, "cursor_normalized"),
+            None
+        );
+    });
+}
+
+#[test]
+fn grabbed_cursor_visibility_is_left_to_the_app() {
+    let mut app = app();
+    let window = app.world.spawn((Window::default(), PrimaryWindow)).id();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_mouse_axis_move(Scenario::Default, MouseAxis::PositiveX, "look_right")
+            .set_cursor_grab(Some(CursorGrabMode::Confined));
+    });
+    app.advance_frames(1);
+    assert!(!app.world.get::<Window>(window).unwrap().cursor.visible);
+    app.world.get_mut::<Window>(window).unwrap().cursor.visible = true;
+    app.advance_frames(2);
+    let cursor = app.world.get::<Window>(window).unwrap().cursor;
+    assert_eq!(cursor.grab_mode, CursorGrabMode::Confined);
+    assert!(cursor.visible);
+}
+
+#[test]
+fn menu_navigation_only_in_menu_scenarios() {
+    let mut app = app();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_menu_navigation(Scenario::Menu, MenuNavigationOptions::default())
+            .bind_keyboard_key_press(Scenario::Default, KeyCode::Up, MenuAction::Up.action_name());
+    });
+    app.press_key(KeyCode::Up, ScanCode(103)).advance_frames(2);
+    assert!(app
+        .take_recorded_actions()
+        .was_started(MenuAction::Up.action_name()));
+    assert_eq!(app.world.resource::<Events<MenuNavigation>>().len(), 0);
+    app.release_key(KeyCode::Up, ScanCode(103))
+        .set_scenario(Scenario::Menu)
+        .advance_frames(1)
+        .press_key(KeyCode::Up, ScanCode(103))
+        .advance_frames(2);
+    let navigation: Vec<MenuNavigation> = app
+        .world
+        .resource_mut::<Events<MenuNavigation>>()
+        .drain()
+        .collect();
+    assert_eq!(navigation.len(), 1);
+    assert_eq!(navigation[0].0, MenuAction::Up);
+}
+
+#[test]
+fn focus_loss_keeps_other_scenarios() {
+    let mut app = app();
+    app.add_event::<WindowFocused>();
+    let window = app.world.spawn((Window::default(), PrimaryWindow)).id();
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_keyboard_key_press(Scenario::Default, KeyCode::Space, "jump")
+            .set_value(Scenario::Menu, "highlight", 1.);
+    });
+    app.press_key(KeyCode::Space, SPACE).advance_frames(2);
+    app.world.send_event(WindowFocused {
+        window,
+        focused: false,
+    });
+    app.advance_frames(2);
+    assert_eq!(app.action_value(Scenario::Default, "jump"), 0.);
+    assert_eq!(app.action_value(Scenario::Menu, "highlight"), 1.);
+    assert!(app
+        .world
+        .resource::<Input<KeyCode>>()
+        .pressed(KeyCode::Space));
+    // The release was missed while unfocused, the next press counts again.
+    app.release_key(KeyCode::Space, SPACE)
+        .advance_frames(1)
+        .press_key(KeyCode::Space, SPACE)
+        .advance_frames(2);
+    assert_eq!(app.action_value(Scenario::Default, "jump"), 1.);
+}
+
+#[test]
+fn unbinding_drops_the_input_threshold() {
+    let mut app = app();
+    let trigger = InputBinding::GamepadButton(GamepadButtonType::RightTrigger2);
+    app.with_input_mapper::<Scenario>(|im| {
+        im.bind_gamepad_trigger_press(
+            Scenario::Default,
+            GamepadButtonType::RightTrigger2,
+            ButtonThreshold {
+                press: 0.5,
+                release: 0.3,
+            },
+            "fire",
+        )
+        .unbind_input(Scenario::Default, trigger)
+        .bind(Scenario::Default, trigger, "accelerate");
+        assert_eq!(im.input_threshold(Scenario::Default, &trigger), None);
+    });
+    let gamepad = Gamepad::new(0);
+    app.connect_gamepad(gamepad, "Xbox Wireless Controller")
+        .advance_frames(1)
+        .set_gamepad_button(gamepad, GamepadButtonType::RightTrigger2, 0.2)
+        .advance_frames(2);
+    assert_eq!(app.action_value(Scenario::Default, "accelerate"), 0.2);
+}